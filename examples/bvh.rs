@@ -0,0 +1,74 @@
+extern crate gusni;
+extern crate rand;
+
+use std::time::SystemTime;
+
+use gusni::{
+    core::{V3, Ray, Scene, WaveLength},
+    tree::{Sphere, Bvh},
+    light::CustomMaterial,
+};
+use rand::Rng;
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    let rays_number = 100_000;
+    let rays = (0..rays_number)
+        .map(|_| {
+            let direction = V3::new(
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+            );
+            Ray::new(
+                V3::new(0.0, 0.0, -200.0),
+                direction.normalize(),
+                WaveLength(550.0),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    for &spheres_number in [1_000, 4_000, 16_000].iter() {
+        let scene = (0..spheres_number)
+            .map(|_| {
+                let center = V3::new(
+                    rng.gen_range(-100.0..100.0),
+                    rng.gen_range(-100.0..100.0),
+                    rng.gen_range(-100.0..100.0),
+                );
+                Sphere::new(
+                    center,
+                    rng.gen_range(0.1..1.0),
+                    CustomMaterial::DiffuseWhite,
+                )
+            })
+            .collect::<Vec<Sphere<CustomMaterial, f64>>>();
+
+        let start = SystemTime::now();
+        let bvh = Bvh::new(scene.clone());
+        let built = SystemTime::now();
+        let bvh_hits = rays
+            .iter()
+            .filter(|ray| bvh.find_intersect(ray).is_some())
+            .count();
+        let bvh_traced = SystemTime::now();
+        let linear_hits = rays
+            .iter()
+            .filter(|ray| scene.find_intersect(ray).is_some())
+            .count();
+        let linear_traced = SystemTime::now();
+        assert_eq!(bvh_hits, linear_hits);
+
+        let build = built.duration_since(start).unwrap();
+        let bvh_time = bvh_traced.duration_since(built).unwrap();
+        let linear_time = linear_traced.duration_since(bvh_traced).unwrap();
+        println!(
+            "spheres: {}, build: {:?}, bvh: {:?}, linear: {:?}, speedup: {:.1}",
+            spheres_number,
+            build,
+            bvh_time,
+            linear_time,
+            linear_time.as_secs_f64() / bvh_time.as_secs_f64(),
+        );
+    }
+}
@@ -1,27 +1,22 @@
 extern crate gusni;
-extern crate serde;
 extern crate serde_json;
-extern crate bincode;
 extern crate rand;
 
-use std::path::Path;
 use std::{
-    fs::File,
-    io::Write,
     time::SystemTime,
     thread,
     sync::{Arc, mpsc},
 };
 
 use gusni::{
-    core::{Buffer, Report, Eye, WaveLengthFactory, WaveLengthTrimmedFactory},
+    core::{
+        Buffer, Report, Eye, ImageOptions, Exposure, ToneMapping, WaveLengthFactory,
+        WaveLengthTrimmedFactory, Tracing,
+    },
     tree::Sphere,
     light::CustomMaterial,
 };
 
-use serde::{Serialize, Deserialize};
-use bincode::serialize;
-
 fn main() {
     let scene: Arc<Vec<Sphere<CustomMaterial, f64>>> =
         Arc::new(serde_json::from_str(include_str!("../scene.json")).unwrap());
@@ -50,7 +45,14 @@ fn main() {
                     interval: 0x1000,
                     sender: &sender,
                 };
-                buffer.trace(&mut rng, &eye, scene.as_ref(), None, Some(report));
+                buffer.trace(
+                    &mut rng,
+                    &eye,
+                    scene.as_ref(),
+                    &Tracing::default(),
+                    None,
+                    Some(report),
+                );
                 let traced = SystemTime::now();
                 let duration = traced.duration_since(start).unwrap();
                 let ray_per_pixel = WaveLengthTrimmedFactory.resolution();
@@ -73,11 +75,11 @@ fn main() {
             .map(|_| (SystemTime::now(), 0))
             .collect::<Vec<_>>();
         for progress in receiver {
-            let &(ref time, ref pixels) = &labels[progress.id];
+            let (time, pixels) = labels[progress.id];
             let new_time = SystemTime::now();
             let new_pixels = progress.index;
-            let delta_time = new_time.duration_since(time.clone()).unwrap();
-            let delta_pixels = new_pixels - pixels.clone();
+            let delta_time = new_time.duration_since(time).unwrap();
+            let delta_pixels = new_pixels - pixels;
             let speed = ((delta_pixels * ray_per_pixel) as f64) / (delta_time.as_micros() as f64);
             println!(
                 "rays per second: {:08.8}, progress: {:0.8}",
@@ -100,53 +102,12 @@ fn main() {
         })
         .unwrap();
 
-    store_tga(&buffer, "target/demo.tga");
+    let options = ImageOptions {
+        exposure: Exposure::Auto { key: 0.18 },
+        tone_mapping: ToneMapping::Aces,
+        ..ImageOptions::default()
+    };
+    buffer.save("target/demo.png", &options).unwrap();
     let written = SystemTime::now();
     println!("total time: {:?}", written.duration_since(start).unwrap());
 }
-
-fn store_tga<P, F>(buffer: &Buffer<F>, path: P)
-where
-    P: AsRef<Path>,
-    F: WaveLengthFactory,
-{
-    #[derive(Serialize, Deserialize)]
-    pub struct TgaHeader {
-        id_length: u8,
-        color_map_type: u8,
-        image_type: u8,
-        color_map: [u8; 5],
-        x_origin: u16,
-        y_origin: u16,
-        width: u16,
-        height: u16,
-        pixel_depth: u8,
-        image_descriptor: u8,
-    }
-
-    impl TgaHeader {
-        pub fn rgb(width: usize, height: usize) -> Self {
-            TgaHeader {
-                id_length: 0,
-                color_map_type: 0,
-                image_type: 2,
-                color_map: [0, 0, 0, 0, 0],
-                x_origin: 0,
-                y_origin: 0,
-                width: width as u16,
-                height: height as u16,
-                pixel_depth: 24,
-                image_descriptor: 0,
-            }
-        }
-    }
-
-    let image_header = TgaHeader::rgb(buffer.width(), buffer.height());
-    let mut file = File::create(path).unwrap();
-    file.write(serialize(&image_header).unwrap().as_slice())
-        .unwrap();
-    let mut b = Vec::with_capacity(buffer.width() * buffer.height() * 3);
-    b.resize(buffer.width() * buffer.height() * 3, 0);
-    buffer.write(3072.0, true, b.as_mut());
-    file.write(b.as_ref()).unwrap();
-}
@@ -1,3 +1,6 @@
+use gusni::core::{
+    ColorSpace, ImageFormat, ImageOptions, Exposure, ToneMapping, ExrOptions, ExrPrecision,
+};
 use std::{
     fmt,
     ffi::OsString,
@@ -15,12 +18,16 @@ pub enum Command {
         state_file: Option<PathBuf>,
     },
     Image {
-        scale: f64,
-        tga_file: PathBuf,
+        options: ImageOptions,
+        file: PathBuf,
     },
     Stop {
         state_file: Option<PathBuf>,
     },
+    Merge {
+        output: PathBuf,
+        inputs: Vec<PathBuf>,
+    },
 }
 
 pub enum Exception {
@@ -37,15 +44,54 @@ pub enum Error {
     TraceWrongSceneFile,
     TraceWrongEyeFile,
     ImageWrongScale(Option<ParseFloatError>),
-    ImageWrongTgaFile,
+    ImageWrongFile,
+    ImageWrongFormat(PathBuf),
+    ImageWrongColorSpace(String),
+    ImageWrongToneMapping(String),
+    ImageWrongExrOption(String),
+    MergeWrongOutput,
+    MergeWrongInputs,
 }
 
 impl fmt::Display for Error {
+    #[allow(clippy::needless_borrowed_reference)]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             &Error::Empty => write!(f, "command is empty"),
             &Error::Unrecognized(ref s) => write!(f, "command \'{}\' is unrecognized", s),
-            _ => unimplemented!(),
+            &Error::TraceWrongWidth(None) => write!(f, "width is missing"),
+            &Error::TraceWrongWidth(Some(ref e)) => write!(f, "width is wrong: {}", e),
+            &Error::TraceWrongHeight(None) => write!(f, "height is missing"),
+            &Error::TraceWrongHeight(Some(ref e)) => write!(f, "height is wrong: {}", e),
+            &Error::TraceWrongThreads(None) => write!(f, "threads number is missing"),
+            &Error::TraceWrongThreads(Some(ref e)) => write!(f, "threads number is wrong: {}", e),
+            &Error::TraceWrongSceneFile => write!(f, "scene file is missing"),
+            &Error::TraceWrongEyeFile => write!(f, "eye file is missing"),
+            &Error::ImageWrongScale(None) => write!(f, "scale or \'auto\' is missing"),
+            &Error::ImageWrongScale(Some(ref e)) => write!(f, "scale is wrong: {}", e),
+            &Error::ImageWrongFile => write!(f, "image file is missing"),
+            &Error::ImageWrongFormat(ref path) => write!(
+                f,
+                "image file \'{}\' has unknown extension, expected tga, png, ppm, pfm or exr",
+                path.display()
+            ),
+            &Error::ImageWrongColorSpace(ref s) => write!(
+                f,
+                "color space \'{}\' is unknown, expected srgb, p3 or rec2020",
+                s
+            ),
+            &Error::ImageWrongToneMapping(ref s) => write!(
+                f,
+                "tone mapping \'{}\' is unknown, expected linear, reinhard, reinhard=<white>, aces or hable",
+                s
+            ),
+            &Error::ImageWrongExrOption(ref s) => write!(
+                f,
+                "exr option \'{}\' is unknown, expected half, float or layers=<variance,samples|none>",
+                s
+            ),
+            &Error::MergeWrongOutput => write!(f, "output state file is missing"),
+            &Error::MergeWrongInputs => write!(f, "input state files are missing"),
         }
     }
 }
@@ -87,15 +133,72 @@ impl Command {
                 })
             },
             "image" => {
-                let scale = s
-                    .next()
-                    .ok_or(Exception::Error(Error::ImageWrongScale(None)))?
-                    .parse()
-                    .map_err(|e| Exception::Error(Error::ImageWrongScale(Some(e))))?;
-                let file = s.next().ok_or(Exception::Error(Error::ImageWrongTgaFile))?;
+                let exposure = match s.next() {
+                    None => return Err(Exception::Error(Error::ImageWrongScale(None))),
+                    Some("auto") => Exposure::default(),
+                    Some(scale) => Exposure::Scale(
+                        scale
+                            .parse()
+                            .map_err(|e| Exception::Error(Error::ImageWrongScale(Some(e))))?,
+                    ),
+                };
+                let file = s.next().ok_or(Exception::Error(Error::ImageWrongFile))?;
+                let file = PathBuf::from(OsString::from(file));
+                if ImageFormat::from_path(&file).is_none() {
+                    return Err(Exception::Error(Error::ImageWrongFormat(file)));
+                }
+                let space = match s.next() {
+                    None | Some("srgb") => ColorSpace::Srgb,
+                    Some("p3") => ColorSpace::DisplayP3,
+                    Some("rec2020") => ColorSpace::Rec2020,
+                    Some(s) => {
+                        return Err(Exception::Error(Error::ImageWrongColorSpace(s.to_owned())))
+                    },
+                };
+                let tone_mapping = match s.next() {
+                    None | Some("linear") => ToneMapping::Linear,
+                    Some("reinhard") => ToneMapping::Reinhard,
+                    Some("aces") => ToneMapping::Aces,
+                    Some("hable") => ToneMapping::Hable,
+                    Some(t) => match t.strip_prefix("reinhard=").map(str::parse) {
+                        Some(Ok(white)) => ToneMapping::ExtendedReinhard { white: white },
+                        _ => {
+                            return Err(Exception::Error(Error::ImageWrongToneMapping(
+                                t.to_owned(),
+                            )))
+                        },
+                    },
+                };
+                // the rest are the precision and the extra layers of the exr image
+                let mut exr = ExrOptions::default();
+                for option in s {
+                    let wrong = || Exception::Error(Error::ImageWrongExrOption(option.to_owned()));
+                    match option {
+                        "half" => exr.precision = ExrPrecision::Half,
+                        "float" => exr.precision = ExrPrecision::Float,
+                        _ => {
+                            let layers = option.strip_prefix("layers=").ok_or_else(wrong)?;
+                            exr.variance = false;
+                            exr.samples = false;
+                            for layer in layers.split(',') {
+                                match layer {
+                                    "variance" => exr.variance = true,
+                                    "samples" => exr.samples = true,
+                                    "none" => (),
+                                    _ => return Err(wrong()),
+                                }
+                            }
+                        },
+                    }
+                }
                 Ok(Command::Image {
-                    scale: scale,
-                    tga_file: PathBuf::from(OsString::from(file)),
+                    options: ImageOptions {
+                        exposure: exposure,
+                        tone_mapping: tone_mapping,
+                        space: space,
+                        exr: exr,
+                    },
+                    file: file,
                 })
             },
             "stop" => {
@@ -104,6 +207,17 @@ impl Command {
                     state_file: file.map(|s| PathBuf::from(OsString::from(s))),
                 })
             },
+            "merge" => {
+                let output = s.next().ok_or(Exception::Error(Error::MergeWrongOutput))?;
+                let inputs: Vec<_> = s.map(|s| PathBuf::from(OsString::from(s))).collect();
+                if inputs.is_empty() {
+                    return Err(Exception::Error(Error::MergeWrongInputs));
+                }
+                Ok(Command::Merge {
+                    output: PathBuf::from(OsString::from(output)),
+                    inputs: inputs,
+                })
+            },
             s => Err(Exception::Error(Error::Unrecognized(s.to_owned()))),
         }
     }
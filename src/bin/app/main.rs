@@ -8,7 +8,7 @@ mod command;
 
 fn main() {
     use self::{
-        tracer::Tracer,
+        tracer::{Tracer, merge},
         command::{Command, Exception},
     };
     use std::io;
@@ -28,17 +28,16 @@ fn main() {
                 scene_file: scene_file,
                 eye_file: eye_file,
                 state_file: state_file,
-            }) => {
-                context = Some(Tracer::start(
-                    width, height, threads, scene_file, eye_file, state_file,
-                ))
+            }) => match Tracer::start(width, height, threads, scene_file, eye_file, state_file) {
+                Ok(tracer) => context = Some(tracer),
+                Err(e) => eprintln!("cannot start: {}", e),
             },
             Ok(Command::Image {
-                scale: scale,
-                tga_file: tga_file,
+                options: options,
+                file: file,
             }) => {
                 if let Some(context) = context.as_ref() {
-                    context.image(scale, tga_file)
+                    context.image(&options, file)
                 }
             },
             Ok(Command::Stop {
@@ -48,6 +47,14 @@ fn main() {
                     Tracer::stop(context, state_file)
                 }
             },
+            Ok(Command::Merge {
+                output: output,
+                inputs: inputs,
+            }) => {
+                if let Err(e) = merge(output, inputs) {
+                    eprintln!("cannot merge: {}", e);
+                }
+            },
         };
     }
 }
@@ -1,10 +1,90 @@
-use gusni::core::{Buffer, Progress, Report, WaveLengthTrimmedFactory, Eye, Scene};
+use gusni::{
+    core::{
+        Buffer, Progress, Report, WaveLengthAnyFactory, WaveLengthSampling, Eye, Scene,
+        ImageOptions, State, StateError, Tracing, EyeDescription, EyeError, ColorMatching,
+        WaveLength, WaveLengthFactory, scene_hash, with_base_directory,
+    },
+    tree::{Primitive, Bvh},
+    light::CustomMaterial,
+};
+use serde::Deserialize;
+use rand::Rng;
 use std::{
-    path::PathBuf,
+    fmt, io, iter,
+    path::{Path, PathBuf},
     thread,
     sync::{mpsc, Arc, Mutex},
 };
 
+pub enum StartError {
+    State(StateError),
+    Eye(EyeError),
+    Read(PathBuf, io::Error),
+    Parse(PathBuf, serde_json::Error),
+    WaveLengths(WaveLengthSampling),
+}
+
+impl fmt::Display for StartError {
+    #[allow(clippy::needless_borrowed_reference)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            &StartError::State(ref e) => write!(f, "{}", e),
+            &StartError::Eye(ref e) => write!(f, "{}", e),
+            &StartError::Read(ref path, ref e) => {
+                write!(f, "cannot read \'{}\': {}", path.display(), e)
+            },
+            &StartError::Parse(ref path, ref e) => {
+                write!(f, "cannot parse \'{}\': {}", path.display(), e)
+            },
+            &StartError::WaveLengths(ref sampling) => {
+                write!(f, "wave lengths {:?} are too few", sampling)
+            },
+        }
+    }
+}
+
+impl From<StateError> for StartError {
+    fn from(e: StateError) -> Self {
+        StartError::State(e)
+    }
+}
+
+impl From<io::Error> for StartError {
+    fn from(e: io::Error) -> Self {
+        StartError::State(StateError::Io(e))
+    }
+}
+
+impl From<EyeError> for StartError {
+    fn from(e: EyeError) -> Self {
+        StartError::Eye(e)
+    }
+}
+
+/// The scene file is either the list of primitives, or the object
+/// with the list and the tracing settings.
+#[derive(Deserialize)]
+struct SceneFile {
+    #[serde(default)]
+    tracing: Tracing,
+    primitives: Vec<Primitive<CustomMaterial, f64>>,
+}
+
+impl SceneFile {
+    // told apart by the first character rather than the untagged enum,
+    // so the error of the primitive reaches the user
+    fn parse(json: &str) -> Result<Self, serde_json::Error> {
+        if json.trim_start().starts_with('[') {
+            serde_json::from_str(json).map(|primitives| SceneFile {
+                tracing: Tracing::default(),
+                primitives: primitives,
+            })
+        } else {
+            serde_json::from_str(json)
+        }
+    }
+}
+
 struct TracerContext {
     handle: Option<thread::JoinHandle<()>>,
     terminate_sender: mpsc::Sender<()>,
@@ -13,11 +93,10 @@ struct TracerContext {
 impl TracerContext {
     pub fn start<E, R, S>(
         id: usize,
-        width: usize,
-        height: usize,
         eye: E,
         scene: R,
-        parent: Arc<Mutex<Buffer<WaveLengthTrimmedFactory>>>,
+        tracing: Arc<Tracing>,
+        parent: Arc<Mutex<Buffer<WaveLengthAnyFactory>>>,
         progress_sender: mpsc::Sender<Progress>,
     ) -> Self
     where
@@ -28,7 +107,10 @@ impl TracerContext {
         let (terminate_sender, terminate_receiver) = mpsc::channel();
         let handle = thread::spawn(move || {
             let mut rng = rand::thread_rng();
-            let factory = WaveLengthTrimmedFactory;
+            let (width, height, factory) = {
+                let parent = parent.lock().unwrap();
+                (parent.width(), parent.height(), parent.factory().clone())
+            };
             let mut buffer = Buffer::new(width, height, None, factory);
             println!("starting {}", id);
             loop {
@@ -36,6 +118,7 @@ impl TracerContext {
                     &mut rng,
                     eye.as_ref(),
                     scene.as_ref(),
+                    tracing.as_ref(),
                     Some(&terminate_receiver),
                     Some(Report {
                         id: id,
@@ -69,7 +152,8 @@ impl TracerContext {
 pub struct Tracer {
     contexts: Vec<TracerContext>,
     progress_receiver: thread::JoinHandle<()>,
-    buffer: Arc<Mutex<Buffer<WaveLengthTrimmedFactory>>>,
+    buffer: Arc<Mutex<Buffer<WaveLengthAnyFactory>>>,
+    scene_hash: u64,
 }
 
 impl Tracer {
@@ -80,41 +164,40 @@ impl Tracer {
         scene_file: PathBuf,
         eye_file: PathBuf,
         state_file: Option<PathBuf>,
-    ) -> Self {
-        use std::{fs, fs::File, io::Read, mem, convert::TryFrom};
-        use gusni::{tree::Sphere, light::CustomMaterial};
+    ) -> Result<Self, StartError> {
+        use std::{fs, fs::File};
 
-        let buffer = if let Some(state_file) = state_file {
-            let mut size = [0; mem::size_of::<u64>() * 2];
-            let mut state_file = File::open(state_file).unwrap();
-            state_file.read_exact(size.as_mut()).unwrap();
-            let width = u64::from_le_bytes(TryFrom::try_from(&size[0x00..0x08]).unwrap()) as usize;
-            let height = u64::from_le_bytes(TryFrom::try_from(&size[0x08..0x10]).unwrap()) as usize;
-
-            let s = mem::size_of::<f64>();
-            let capacity = width * height * 3 * s;
-            let mut byte_buffer = vec![0; capacity];
-            state_file.read_exact(byte_buffer.as_mut()).unwrap();
-
-            let mut data = Vec::with_capacity(width * height * 3);
-            data.resize(capacity, 0.0);
-
-            for i in 0..(width * height * 3) {
-                let a = TryFrom::try_from(&byte_buffer[(i * s)..((i + 1) * s)]).unwrap();
-                data[i] = f64::from_le_bytes(a);
-            }
+        let read = |path: &PathBuf| {
+            fs::read_to_string(path).map_err(|e| StartError::Read(path.clone(), e))
+        };
+        let scene_json = read(&scene_file)?;
+        let eye_json = read(&eye_file)?;
+        let scene_hash = scene_hash(scene_json.as_bytes(), eye_json.as_bytes());
 
-            Buffer::new(width, height, Some(data), WaveLengthTrimmedFactory)
+        let scene_directory = scene_file.parent().unwrap_or_else(|| Path::new(""));
+        let scene = with_base_directory(scene_directory, || SceneFile::parse(scene_json.as_str()))
+            .map_err(|e| StartError::Parse(scene_file.clone(), e))?;
+        let factory = scene
+            .tracing
+            .wave_lengths
+            .factory(&scene.tracing.observer)
+            .ok_or_else(|| StartError::WaveLengths(scene.tracing.wave_lengths.clone()))?;
+
+        let buffer = if let Some(state_file) = state_file {
+            let mut state_file = File::open(state_file)?;
+            let state = State::read(&mut state_file, factory)?;
+            state.validate(width, height, scene_hash)?;
+            state.buffer
         } else {
-            Buffer::new(width, height, None, WaveLengthTrimmedFactory)
+            Buffer::new(width, height, None, factory)
         };
         let buffer = Arc::new(Mutex::new(buffer));
 
-        let scene_json = fs::read_to_string(scene_file.as_path()).unwrap();
-        let scene: Arc<Vec<Sphere<CustomMaterial, f64>>> =
-            Arc::new(serde_json::from_str(scene_json.as_str()).unwrap());
-        let eye_json = fs::read_to_string(eye_file.as_path()).unwrap();
-        let eye: Arc<Eye<f64>> = Arc::new(serde_json::from_str(eye_json.as_str()).unwrap());
+        let tracing = Arc::new(scene.tracing);
+        let scene = Arc::new(Bvh::new(scene.primitives));
+        let eye: EyeDescription<f64> = serde_json::from_str(eye_json.as_str())
+            .map_err(|e| StartError::Parse(eye_file.clone(), e))?;
+        let eye = Arc::new(eye.build(width, height)?);
 
         let (progress_sender, progress_receiver) = mpsc::channel();
 
@@ -124,17 +207,16 @@ impl Tracer {
                 let scene = scene.clone();
                 TracerContext::start(
                     thread_id,
-                    width,
-                    height,
                     eye,
                     scene,
+                    tracing.clone(),
                     buffer.clone(),
                     progress_sender.clone(),
                 )
             })
             .collect();
 
-        Tracer {
+        Ok(Tracer {
             contexts: contexts,
             progress_receiver: thread::spawn(move || {
                 progress_receiver.into_iter().for_each(|progress| {
@@ -142,51 +224,92 @@ impl Tracer {
                 })
             }),
             buffer: buffer,
-        }
+            scene_hash: scene_hash,
+        })
     }
 
-    pub fn image(&self, scale: f64, tga_file: PathBuf) {
-        use std::{fs::File, io::Write};
-
+    pub fn image(&self, options: &ImageOptions, file: PathBuf) {
         let buffer = self.buffer.lock().unwrap();
-
-        let mut tga_header = [0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 24, 0];
-        tga_header[12..14].clone_from_slice(&(buffer.width() as u16).to_le_bytes());
-        tga_header[14..16].clone_from_slice(&(buffer.height() as u16).to_le_bytes());
-
-        let mut tga_buffer = Vec::new();
-        tga_buffer.resize(3 * buffer.width() * buffer.height(), 0);
-        buffer.write(scale, true, tga_buffer.as_mut());
-
-        let mut tga_file = File::create(tga_file).unwrap();
-        tga_file.write_all(tga_header.as_ref()).unwrap();
-        tga_file.write_all(tga_buffer.as_ref()).unwrap();
+        if let Err(e) = buffer.save(file, options) {
+            eprintln!("cannot write image: {}", e);
+        }
     }
 
     pub fn stop(self, state_file: Option<PathBuf>) {
-        use std::{fs::File, io::Write, mem};
+        use std::{fs::File, io::BufWriter, mem};
 
         self.contexts.into_iter().for_each(|mut context| {
             context.stop();
         });
         self.progress_receiver.join().unwrap();
         if let Some(state_file) = state_file {
-            let buffer = self.buffer.lock().unwrap();
-
-            let mut size = [0; mem::size_of::<u64>() * 2];
-            size[0x00..0x08].clone_from_slice(&(buffer.width() as u64).to_le_bytes());
-            size[0x08..0x10].clone_from_slice(&(buffer.height() as u64).to_le_bytes());
-            let mut state_file = File::create(state_file).unwrap();
-            state_file.write_all(size.as_ref()).unwrap();
-
-            let s = mem::size_of::<f64>();
-            let capacity = s * buffer.data().len();
-            let mut byte_buffer = vec![0; capacity];
-            for i in 0..buffer.data().len() {
-                let f = buffer.data()[i];
-                byte_buffer[(i * s)..((i + 1) * s)].clone_from_slice(&f.to_le_bytes());
+            let mut buffer = self.buffer.lock().unwrap();
+            let factory = buffer.factory().clone();
+            let buffer = mem::replace(&mut *buffer, Buffer::new(0, 0, None, factory));
+            let state = State::new(buffer, self.scene_hash);
+            let result =
+                File::create(state_file).and_then(|file| state.write(&mut BufWriter::new(file)));
+            if let Err(e) = result {
+                eprintln!("cannot write state: {}", e);
             }
-            state_file.write_all(byte_buffer.as_ref()).unwrap();
         }
     }
 }
+
+/// The factory of the merged state files, it is only compared and written back,
+/// so it keeps the identity of the file with the observer of the importance one.
+struct MergedFactory {
+    identity: String,
+    resolution: usize,
+}
+
+impl WaveLengthFactory for MergedFactory {
+    type Iter = iter::Empty<(WaveLength, f64)>;
+
+    fn iter<R>(&self, rng: &mut R) -> Self::Iter
+    where
+        R: Rng,
+    {
+        let _ = rng;
+        iter::empty()
+    }
+
+    fn resolution(&self) -> usize {
+        self.resolution
+    }
+
+    fn identity(&self) -> String {
+        self.identity.clone()
+    }
+}
+
+/// Sums the state files of the independent renders of the same scene.
+pub fn merge(output: PathBuf, inputs: Vec<PathBuf>) -> Result<(), StateError> {
+    use std::{fs::File, io::BufWriter};
+
+    // the resolution does not depend on the observer
+    let factory = |identity: &str| {
+        WaveLengthSampling::from_identity(identity)
+            .and_then(|sampling| sampling.factory(&ColorMatching::default()))
+            .map(|factory| MergedFactory {
+                identity: identity.to_owned(),
+                resolution: factory.resolution(),
+            })
+            .ok_or_else(|| StateError::Factory {
+                expected: "trimmed, linear, uniform, stratified or importance".to_owned(),
+                found: identity.to_owned(),
+            })
+    };
+    let mut merged: Option<State<MergedFactory>> = None;
+    for input in inputs {
+        let state = State::read_with(&mut File::open(input)?, factory)?;
+        match merged.as_mut() {
+            Some(merged) => merged.merge(state)?,
+            None => merged = Some(state),
+        }
+    }
+    if let Some(merged) = merged {
+        merged.write(&mut BufWriter::new(File::create(output)?))?;
+    }
+    Ok(())
+}
@@ -20,6 +20,18 @@ where
         V3 { x: x, y: y, z: z }
     }
 
+    pub fn x(&self) -> C {
+        self.x
+    }
+
+    pub fn y(&self) -> C {
+        self.y
+    }
+
+    pub fn z(&self) -> C {
+        self.z
+    }
+
     pub fn cross(&self, rhs: &Self) -> Self {
         V3 {
             x: self.y * rhs.z - self.z * rhs.y,
@@ -28,13 +40,37 @@ where
         }
     }
 
+    pub fn abs(&self) -> Self {
+        V3::new(self.x.abs(), self.y.abs(), self.z.abs())
+    }
+
+    pub fn min(&self, rhs: &Self) -> Self {
+        V3::new(self.x.min(rhs.x), self.y.min(rhs.y), self.z.min(rhs.z))
+    }
+
+    pub fn max(&self, rhs: &Self) -> Self {
+        V3::new(self.x.max(rhs.x), self.y.max(rhs.y), self.z.max(rhs.z))
+    }
+
     pub fn normalize(&self) -> Self {
         let l = (self * self).sqrt();
         self / l
     }
+
+    /// Two unit vectors orthogonal to this unit vector and to each other.
+    pub fn frame(&self) -> (Self, Self) {
+        let a = if self.x.abs() > C::from(0.5).unwrap() {
+            V3::new(C::zero(), C::one(), C::zero())
+        } else {
+            V3::new(C::one(), C::zero(), C::zero())
+        };
+        let s = a.cross(self).normalize();
+        let t = self.cross(&s);
+        (s, t)
+    }
 }
 
-impl<'a, 'b, C> Mul<&'b V3<C>> for &'a V3<C>
+impl<'b, C> Mul<&'b V3<C>> for &V3<C>
 where
     C: Float,
 {
@@ -45,7 +81,7 @@ where
     }
 }
 
-impl<'a, C> Mul<C> for &'a V3<C>
+impl<C> Mul<C> for &V3<C>
 where
     C: Float,
 {
@@ -56,7 +92,7 @@ where
     }
 }
 
-impl<'a, C> Div<C> for &'a V3<C>
+impl<C> Div<C> for &V3<C>
 where
     C: Float,
 {
@@ -67,7 +103,7 @@ where
     }
 }
 
-impl<'a, 'b, C> Add<&'b V3<C>> for &'a V3<C>
+impl<'b, C> Add<&'b V3<C>> for &V3<C>
 where
     C: Float,
 {
@@ -78,7 +114,7 @@ where
     }
 }
 
-impl<'a, 'b, C> Sub<&'b V3<C>> for &'a V3<C>
+impl<'b, C> Sub<&'b V3<C>> for &V3<C>
 where
     C: Float,
 {
@@ -89,7 +125,7 @@ where
     }
 }
 
-impl<'a, C> Neg for &'a V3<C>
+impl<C> Neg for &V3<C>
 where
     C: Float,
 {
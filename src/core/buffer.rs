@@ -1,44 +1,18 @@
-use super::algebra::V3;
-use super::ray::Ray;
+use super::eye::Eye;
+use super::ray::Tracing;
 use super::scene::Scene;
-use super::wave::{WaveLength, Rgb, WaveLengthFactory};
+use super::wave::WaveLengthFactory;
+use super::color::{Xyz, Rgb};
+use super::tone::Exposure;
+use super::image::ImageOptions;
 
 use std::{
     ops::{Add, AddAssign},
     sync::mpsc,
 };
-use serde::{Serialize, Deserialize};
 use num::Float;
 use rand::Rng;
 
-#[derive(Clone, Serialize, Deserialize)]
-pub struct Eye<C>
-where
-    C: Float,
-{
-    pub position: V3<C>,
-    pub forward: V3<C>,
-    pub right: V3<C>,
-    pub up: V3<C>,
-
-    pub width: C,
-    pub height: C,
-    pub distance: C,
-}
-
-impl<C> Eye<C>
-where
-    C: Float,
-{
-    pub fn ray(&self, x: C, y: C, width: usize, height: usize, wave_length: WaveLength) -> Ray<C> {
-        let x = self.width * (x / C::from(width).unwrap() - C::from(0.5).unwrap());
-        let y = self.height * (y / C::from(height).unwrap() - C::from(0.5).unwrap());
-        let tangent = &(&self.right * x) + &(&self.up * y);
-        let direction = (&(&self.forward * self.distance) + &tangent).normalize();
-        Ray::new(self.position.clone(), direction, wave_length)
-    }
-}
-
 #[derive(Debug)]
 pub struct Progress {
     pub id: usize,
@@ -52,6 +26,8 @@ pub struct Report<'a> {
     pub sender: &'a mpsc::Sender<Progress>,
 }
 
+/// Accumulates CIE XYZ of the traced photons, three values per pixel,
+/// and the sum of squares of the luminance of each sample, one value per pixel.
 #[derive(Clone)]
 pub struct Buffer<F>
 where
@@ -61,6 +37,7 @@ where
     width: usize,
     height: usize,
     data: Vec<f64>,
+    squares: Vec<f64>,
     sample_count: usize,
 }
 
@@ -79,10 +56,29 @@ where
                 data.resize(capacity, 0.0);
                 data
             }),
+            squares: vec![0.0; width * height],
             sample_count: 0,
         }
     }
 
+    pub(super) fn from_parts(
+        width: usize,
+        height: usize,
+        data: Vec<f64>,
+        squares: Vec<f64>,
+        sample_count: usize,
+        factory: F,
+    ) -> Self {
+        Buffer {
+            factory: factory,
+            width: width,
+            height: height,
+            data: data,
+            squares: squares,
+            sample_count: sample_count,
+        }
+    }
+
     pub fn sample_count(&self) -> usize {
         self.sample_count
     }
@@ -95,15 +91,25 @@ where
         self.height
     }
 
+    pub fn factory(&self) -> &F {
+        &self.factory
+    }
+
     pub fn data(&self) -> &[f64] {
         self.data.as_ref()
     }
 
+    pub fn squares(&self) -> &[f64] {
+        self.squares.as_ref()
+    }
+
+    #[allow(clippy::needless_borrowed_reference, clippy::manual_is_multiple_of)]
     pub fn trace<S, C, R>(
         &mut self,
         rng: &mut R,
         eye: &Eye<C>,
         scene: &S,
+        tracing: &Tracing,
         terminate_receiver: Option<&mpsc::Receiver<()>>,
         report: Option<Report<'_>>,
     ) -> bool
@@ -112,6 +118,7 @@ where
         C: Float,
         R: Rng,
     {
+        let lights = scene.lights();
         for i in 0..self.height {
             for j in 0..self.width {
                 let index = i * self.width + j;
@@ -129,20 +136,32 @@ where
                     self.data[index * 3 + 0] = 0.0;
                     self.data[index * 3 + 1] = 0.0;
                     self.data[index * 3 + 2] = 0.0;
+                    self.squares[index] = 0.0;
                 }
-                for l in self.factory.iter() {
-                    let color = l.color();
+                let mut luminance = 0.0;
+                for (l, weight) in self.factory.iter(rng) {
+                    // the path may change the wave length on the fluorescent surfaces,
+                    // but the photon reaches the eye with this one
+                    let color = tracing.observer.xyz(&l);
                     let dx = rng.gen_range(-0.5..0.5);
                     let dy = rng.gen_range(-0.5..0.5);
                     let x = C::from(j).unwrap() + C::from(dx).unwrap();
                     let y = C::from(i).unwrap() + C::from(dy).unwrap();
-                    let ray = eye.ray(x, y, self.width, self.height, l);
-                    let photon = ray.trace(scene, rng);
-                    let (r, g, b) = (color * photon).tuple(false);
-                    self.data[index * 3 + 0] += r;
-                    self.data[index * 3 + 1] += g;
-                    self.data[index * 3 + 2] += b;
+                    let lens = (
+                        C::from(rng.gen_range(0.0..1.0)).unwrap(),
+                        C::from(rng.gen_range(0.0..1.0)).unwrap(),
+                    );
+                    let photon = match eye.ray(x, y, lens, self.width, self.height, l) {
+                        Some(ray) => ray.trace(scene, lights.as_ref(), tracing, rng),
+                        None => 0.0,
+                    };
+                    let (x, y, z) = (color * (photon * weight)).tuple();
+                    self.data[index * 3 + 0] += x;
+                    self.data[index * 3 + 1] += y;
+                    self.data[index * 3 + 2] += z;
+                    luminance += y;
                 }
+                self.squares[index] += luminance * luminance;
                 if let Some(terminate_receiver) = terminate_receiver {
                     if let Ok(()) = terminate_receiver.try_recv() {
                         self.sample_count = 0;
@@ -156,15 +175,61 @@ where
         true
     }
 
-    pub fn write(&self, scale: f64, reverse: bool, buffer: &mut [u8]) {
+    /// Mean XYZ of the pixel over all traced samples and wave lengths.
+    pub fn xyz(&self, index: usize) -> Xyz {
+        if self.sample_count == 0 {
+            Xyz::default()
+        } else {
+            let tuple = &self.data[(index * 3)..(index * 3 + 3)];
+            let color = Xyz::new(tuple[0], tuple[1], tuple[2]);
+            color * (1.0 / ((self.sample_count * self.factory.resolution()) as f64))
+        }
+    }
+
+    /// Unbiased variance of the luminance of one sample of the pixel,
+    /// in the same units as `Buffer::xyz`.
+    pub fn variance(&self, index: usize) -> f64 {
+        if self.sample_count < 2 {
+            0.0
+        } else {
+            let n = self.sample_count as f64;
+            let resolution = self.factory.resolution() as f64;
+            let sum = self.data[index * 3 + 1];
+            let variance = (self.squares[index] - sum * sum / n) / (n - 1.0);
+            variance.max(0.0) / (resolution * resolution)
+        }
+    }
+
+    /// The factor applied to `Buffer::xyz` before the tone mapping.
+    /// The automatic exposure takes the log-average luminance of the pixels which received light.
+    pub fn exposure(&self, exposure: &Exposure) -> f64 {
+        match exposure {
+            &Exposure::Scale(scale) => scale,
+            &Exposure::Auto { key: key } => {
+                let (sum, count) = (0..(self.width * self.height))
+                    .map(|index| self.xyz(index).tuple().1)
+                    .filter(|&y| y > 0.0)
+                    .fold((0.0, 0), |(sum, count), y| (sum + y.ln(), count + 1));
+                if count == 0 {
+                    1.0
+                } else {
+                    key / (sum / (count as f64)).exp()
+                }
+            },
+        }
+    }
+
+    /// Converts the accumulated XYZ into the color space, applies the exposure and
+    /// the tone mapping and encodes 8 bit per channel.
+    pub fn write(&self, options: &ImageOptions, reverse: bool, buffer: &mut [u8]) {
         if self.sample_count != 0 {
-            let mut position = 0;
-            for tuple in self.data.chunks(3) {
-                let color = Rgb::new(tuple[0], tuple[1], tuple[2]);
-                let color =
-                    color * (scale / ((self.sample_count * self.factory.resolution()) as f64));
-                color.write(reverse, &mut buffer[position..(position + 3)]);
-                position += 3;
+            let scale = self.exposure(&options.exposure);
+            let space = options.space;
+            let encode = |c: f64| space.encode(options.tone_mapping.map(c));
+            for index in 0..(self.width * self.height) {
+                let (r, g, b) = space.linear(&(self.xyz(index) * scale)).tuple(false);
+                let color = Rgb::new(encode(r), encode(g), encode(b));
+                color.write(reverse, &mut buffer[(index * 3)..(index * 3 + 3)]);
             }
         }
     }
@@ -186,6 +251,9 @@ where
         for i in 0..(self.height * self.width * 3) {
             self.data[i] += rhs.data[i];
         }
+        for i in 0..(self.height * self.width) {
+            self.squares[i] += rhs.squares[i];
+        }
 
         rhs.sample_count = 0;
     }
@@ -0,0 +1,173 @@
+use std::ops::{Add, Mul};
+use serde::{Serialize, Deserialize};
+
+/// CIE XYZ tristimulus values.
+#[derive(Default, Clone, Serialize, Deserialize, Debug)]
+pub struct Xyz {
+    pub(super) x: f64,
+    pub(super) y: f64,
+    pub(super) z: f64,
+}
+
+impl Xyz {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Xyz { x: x, y: y, z: z }
+    }
+
+    pub fn tuple(&self) -> (f64, f64, f64) {
+        (self.x, self.y, self.z)
+    }
+}
+
+impl Mul<f64> for Xyz {
+    type Output = Xyz;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        Xyz::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl Add for Xyz {
+    type Output = Xyz;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Xyz::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+#[derive(Default, Clone, Serialize, Deserialize, Debug)]
+pub struct Rgb {
+    r: f64,
+    g: f64,
+    b: f64,
+}
+
+impl Rgb {
+    pub fn new(r: f64, g: f64, b: f64) -> Self {
+        Rgb { r: r, g: g, b: b }
+    }
+
+    pub fn tuple(&self, reverse: bool) -> (f64, f64, f64) {
+        if reverse {
+            (self.b, self.g, self.r)
+        } else {
+            (self.r, self.g, self.b)
+        }
+    }
+
+    pub fn write(&self, reverse: bool, buffer: &mut [u8]) {
+        let to_byte = |a: f64| -> u8 {
+            if a >= 1.0 {
+                255
+            } else if a <= 0.0 {
+                0
+            } else {
+                (a * 255.0) as u8
+            }
+        };
+        let (a, b, c) = self.tuple(reverse);
+        buffer[0] = to_byte(a);
+        buffer[1] = to_byte(b);
+        buffer[2] = to_byte(c);
+    }
+}
+
+impl Mul<f64> for Rgb {
+    type Output = Rgb;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        Rgb::new(self.r * rhs, self.g * rhs, self.b * rhs)
+    }
+}
+
+impl Add for Rgb {
+    type Output = Rgb;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Rgb::new(self.r + rhs.r, self.g + rhs.g, self.b + rhs.b)
+    }
+}
+
+/// RGB color space of the output image, all of them have the D65 white point.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+pub enum ColorSpace {
+    #[default]
+    Srgb,
+    DisplayP3,
+    Rec2020,
+}
+
+impl ColorSpace {
+    #[rustfmt::skip]
+    fn matrix(&self) -> [[f64; 3]; 3] {
+        match self {
+            &ColorSpace::Srgb => [
+                [ 3.2404542, -1.5371385, -0.4985314],
+                [-0.9692660,  1.8760108,  0.0415560],
+                [ 0.0556434, -0.2040259,  1.0572252],
+            ],
+            &ColorSpace::DisplayP3 => [
+                [ 2.4934969, -0.9313836, -0.4027108],
+                [-0.8294890,  1.7626641,  0.0236247],
+                [ 0.0358458, -0.0761724,  0.9568845],
+            ],
+            &ColorSpace::Rec2020 => [
+                [ 1.7166512, -0.3556708, -0.2533663],
+                [-0.6666844,  1.6164812,  0.0157685],
+                [ 0.0176399, -0.0427706,  0.9421031],
+            ],
+        }
+    }
+
+    /// Linear RGB, components are negative for colors outside of the gamut.
+    pub fn linear(&self, xyz: &Xyz) -> Rgb {
+        let m = self.matrix();
+        let (x, y, z) = xyz.tuple();
+        let row = |i: usize| m[i][0] * x + m[i][1] * y + m[i][2] * z;
+        Rgb::new(row(0), row(1), row(2))
+    }
+
+    /// Applies the transfer function of the color space to the linear value.
+    pub fn encode(&self, linear: f64) -> f64 {
+        let c = linear.max(0.0);
+        match self {
+            &ColorSpace::Srgb | &ColorSpace::DisplayP3 => {
+                if c <= 0.0031308 {
+                    12.92 * c
+                } else {
+                    1.055 * c.powf(1.0 / 2.4) - 0.055
+                }
+            },
+            &ColorSpace::Rec2020 => {
+                if c < 0.018053968510807 {
+                    4.5 * c
+                } else {
+                    1.09929682680944 * c.powf(0.45) - 0.09929682680944
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Xyz, ColorSpace};
+
+    #[test]
+    fn white() {
+        let d65 = Xyz::new(0.95047, 1.0, 1.08883);
+        for &space in [ColorSpace::Srgb, ColorSpace::DisplayP3, ColorSpace::Rec2020].iter() {
+            let (r, g, b) = space.linear(&d65).tuple(false);
+            assert!((r - 1.0).abs() < 1e-3);
+            assert!((g - 1.0).abs() < 1e-3);
+            assert!((b - 1.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn transfer() {
+        assert!((ColorSpace::Srgb.encode(0.5) - 0.735357).abs() < 1e-6);
+        assert!((ColorSpace::Srgb.encode(0.001) - 0.01292).abs() < 1e-9);
+        assert_eq!(ColorSpace::Srgb.encode(-1.0), 0.0);
+    }
+}
@@ -0,0 +1,485 @@
+use super::algebra::V3;
+use super::ray::Ray;
+use super::wave::WaveLength;
+
+use std::fmt;
+use serde::{Serialize, Deserialize, Deserializer, de::Error};
+use serde_json::{Value, Map};
+use num::Float;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(deserialize = "C: Deserialize<'de>"))]
+pub struct Eye<C>
+where
+    C: Float,
+{
+    pub position: V3<C>,
+    pub forward: V3<C>,
+    pub right: V3<C>,
+    pub up: V3<C>,
+
+    pub width: C,
+    pub height: C,
+    pub distance: C,
+
+    /// Perspective if absent.
+    #[serde(default)]
+    pub projection: Projection<C>,
+    /// Pinhole if absent.
+    pub lens: Option<Lens<C>>,
+}
+
+/// The eye file, either the basis given explicitly or the look-at description,
+/// the latter is recognized by the field `from`.
+#[derive(Clone)]
+pub enum EyeDescription<C>
+where
+    C: Float,
+{
+    Basis(Eye<C>),
+    LookAt(LookAt<C>),
+}
+
+impl<'de, C> Deserialize<'de> for EyeDescription<C>
+where
+    C: Float + Deserialize<'de>,
+{
+    // the object is buffered to look at its fields first, so the error
+    // of the recognized description is reported as it is
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let object = Map::<String, Value>::deserialize(deserializer)?;
+        if object.contains_key("from") {
+            LookAt::deserialize(Value::Object(object))
+                .map(EyeDescription::LookAt)
+                .map_err(|e| D::Error::custom(format!("look-at eye: {}", e)))
+        } else {
+            Eye::deserialize(Value::Object(object))
+                .map(EyeDescription::Basis)
+                .map_err(|e| D::Error::custom(format!("eye basis: {}", e)))
+        }
+    }
+}
+
+/// The eye at `from` looking at `to`, the `up` is only the hint, it need not be orthogonal.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(deserialize = "C: Deserialize<'de>"))]
+pub struct LookAt<C>
+where
+    C: Float,
+{
+    pub from: V3<C>,
+    pub to: V3<C>,
+    pub up: V3<C>,
+    /// Vertical field of view in degrees, the screen is at `to` and its aspect ratio
+    /// is the one of the image.
+    pub fov: C,
+
+    #[serde(default)]
+    pub projection: Projection<C>,
+    pub lens: Option<Lens<C>>,
+}
+
+#[derive(Debug)]
+pub enum EyeError {
+    /// `from` and `to` coincide, or `up` is parallel to the view direction.
+    Degenerate,
+    NotNormalized,
+    NotOrthogonal,
+    WrongScreen,
+}
+
+impl fmt::Display for EyeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            &EyeError::Degenerate => write!(
+                f,
+                "eye basis is degenerate, the eye looks at itself or along the up direction"
+            ),
+            &EyeError::NotNormalized => {
+                write!(
+                    f,
+                    "eye forward, right and up vectors should be unit vectors"
+                )
+            },
+            &EyeError::NotOrthogonal => {
+                write!(f, "eye forward, right and up vectors should be orthogonal")
+            },
+            &EyeError::WrongScreen => {
+                write!(
+                    f,
+                    "eye screen width, height and distance should be positive"
+                )
+            },
+        }
+    }
+}
+
+impl<C> EyeDescription<C>
+where
+    C: Float,
+{
+    /// The eye for the image of the resolution.
+    pub fn build(self, width: usize, height: usize) -> Result<Eye<C>, EyeError> {
+        let eye = match self {
+            EyeDescription::Basis(eye) => eye,
+            EyeDescription::LookAt(look_at) => look_at.build(width, height)?,
+        };
+        eye.validate()?;
+        Ok(eye)
+    }
+}
+
+impl<C> LookAt<C>
+where
+    C: Float,
+{
+    pub fn build(self, width: usize, height: usize) -> Result<Eye<C>, EyeError> {
+        let tolerance = C::epsilon().sqrt();
+
+        let view = &self.to - &self.from;
+        let distance = (&view * &view).sqrt();
+        if distance <= tolerance {
+            return Err(EyeError::Degenerate);
+        }
+        let forward = &view / distance;
+        let right = self.up.cross(&forward);
+        let length = (&right * &right).sqrt();
+        if length <= tolerance * (&self.up * &self.up).sqrt() {
+            return Err(EyeError::Degenerate);
+        }
+        let right = &right / length;
+        let up = forward.cross(&right);
+
+        let half = C::from(0.5).unwrap();
+        let screen_height = C::from(2.0).unwrap() * distance * (self.fov.to_radians() * half).tan();
+        let aspect = C::from(width).unwrap() / C::from(height).unwrap();
+        Ok(Eye {
+            position: self.from,
+            forward: forward,
+            right: right,
+            up: up,
+            width: screen_height * aspect,
+            height: screen_height,
+            distance: distance,
+            projection: self.projection,
+            lens: self.lens,
+        })
+    }
+}
+
+/// How the point of the screen maps to the direction of the ray.
+#[derive(Clone, Serialize, Deserialize, Default)]
+pub enum Projection<C>
+where
+    C: Float,
+{
+    /// The screen of `width` by `height` at the `distance` from the position.
+    #[default]
+    Perspective,
+    /// Parallel rays from the screen of `width` by `height` around the position.
+    Orthographic,
+    /// Equidistant fisheye, the angle from `forward` is proportional to the distance
+    /// from the center of the screen, and it is half of the `fov` in degrees
+    /// at the left and right edges. The screen is black outside of the image circle.
+    Fisheye { fov: C },
+    /// Full panorama, the longitude along the width and the latitude along the height.
+    Equirectangular,
+}
+
+/// Thin lens in the plane of `right` and `up`, sharp at the `focus` distance along `forward`.
+/// Only the perspective and the orthographic projections have it.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Lens<C>
+where
+    C: Float,
+{
+    pub radius: C,
+    pub focus: C,
+    /// Number of the aperture blades, the aperture is the regular polygon
+    /// inscribed in the circle of the `radius`, round if absent.
+    pub blades: Option<usize>,
+}
+
+impl<C> Lens<C>
+where
+    C: Float,
+{
+    /// Point on the aperture as the offsets along `right` and `up`,
+    /// `u` and `v` are uniform in `[0, 1)`.
+    fn sample(&self, u: C, v: C) -> (C, C) {
+        use std::f64::consts::TAU;
+
+        let tau = C::from(TAU).unwrap();
+        match self.blades {
+            Some(blades) if blades >= 3 => {
+                // pick the triangle between the center and the edge, then the point in it
+                let n = C::from(blades).unwrap();
+                let i = (v * n).floor().min(n - C::one());
+                let t = v * n - i;
+                let s = u.sqrt();
+                let (a, b) = (i * tau / n, (i + C::one()) * tau / n);
+                let x = s * ((C::one() - t) * a.cos() + t * b.cos());
+                let y = s * ((C::one() - t) * a.sin() + t * b.sin());
+                (self.radius * x, self.radius * y)
+            },
+            _ => {
+                let r = self.radius * u.sqrt();
+                let a = tau * v;
+                (r * a.cos(), r * a.sin())
+            },
+        }
+    }
+}
+
+impl<C> Eye<C>
+where
+    C: Float,
+{
+    /// Checks the basis is orthonormal and the screen is not empty.
+    pub fn validate(&self) -> Result<(), EyeError> {
+        let tolerance = C::from(1e-6).unwrap();
+
+        let vectors = [&self.forward, &self.right, &self.up];
+        for &v in vectors.iter() {
+            if ((v * v).sqrt() - C::one()).abs() > tolerance {
+                return Err(EyeError::NotNormalized);
+            }
+        }
+        for i in 0..3 {
+            let d = vectors[i] * vectors[(i + 1) % 3];
+            if d.abs() > tolerance {
+                return Err(EyeError::NotOrthogonal);
+            }
+        }
+        if self.width <= C::zero() || self.height <= C::zero() || self.distance <= C::zero() {
+            return Err(EyeError::WrongScreen);
+        }
+        Ok(())
+    }
+
+    /// The ray through the point of the screen, `lens` is uniform in `[0, 1)`
+    /// and chooses the point on the aperture.
+    /// `None` if the point is outside of the image of the projection.
+    pub fn ray(
+        &self,
+        x: C,
+        y: C,
+        lens: (C, C),
+        width: usize,
+        height: usize,
+        wave_length: WaveLength,
+    ) -> Option<Ray<C>> {
+        use std::f64::consts::PI;
+
+        let half = C::from(0.5).unwrap();
+        let pi = C::from(PI).unwrap();
+
+        // from minus half to half
+        let x = x / C::from(width).unwrap() - half;
+        let y = y / C::from(height).unwrap() - half;
+
+        // only the planar projections are focused by the lens
+        let (origin, direction, planar) = match &self.projection {
+            &Projection::Perspective => {
+                let tangent = &(&self.right * (self.width * x)) + &(&self.up * (self.height * y));
+                let direction = (&(&self.forward * self.distance) + &tangent).normalize();
+                (self.position.clone(), direction, true)
+            },
+            &Projection::Orthographic => {
+                let tangent = &(&self.right * (self.width * x)) + &(&self.up * (self.height * y));
+                (&self.position + &tangent, self.forward.clone(), true)
+            },
+            &Projection::Fisheye { fov: fov } => {
+                let (x, y) = (self.width * x, self.height * y);
+                let r = (x * x + y * y).sqrt();
+                let angle = r / (self.width * half) * (fov.to_radians() * half);
+                // outside of the image circle, or beyond the opposite direction
+                if r > self.width * half || angle > pi {
+                    return None;
+                }
+                let direction = if r > C::zero() {
+                    let tangent = &(&(&self.right * x) + &(&self.up * y)) / r;
+                    &(&self.forward * angle.cos()) + &(&tangent * angle.sin())
+                } else {
+                    self.forward.clone()
+                };
+                (self.position.clone(), direction, false)
+            },
+            &Projection::Equirectangular => {
+                let longitude = x * pi * C::from(2.0).unwrap();
+                let latitude = y * pi;
+                let horizontal =
+                    &(&self.forward * longitude.cos()) + &(&self.right * longitude.sin());
+                let direction = &(&horizontal * latitude.cos()) + &(&self.up * latitude.sin());
+                (self.position.clone(), direction, false)
+            },
+        };
+
+        let ray = match &self.lens {
+            Some(l) if planar => {
+                let focus = &origin + &(&direction * (l.focus / (&direction * &self.forward)));
+                let (u, v) = l.sample(lens.0, lens.1);
+                let origin = &(&origin + &(&self.right * u)) + &(&self.up * v);
+                let direction = (&focus - &origin).normalize();
+                Ray::new(origin, direction, wave_length)
+            },
+            _ => Ray::new(origin, direction, wave_length),
+        };
+        Some(ray)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Eye, EyeDescription, EyeError, LookAt, Lens, Projection};
+    use crate::core::{V3, WaveLength};
+    use rand::Rng;
+
+    fn eye() -> Eye<f64> {
+        serde_json::from_str(include_str!("../../eye.json")).unwrap()
+    }
+
+    fn close(a: &V3<f64>, b: &V3<f64>) -> bool {
+        let d = a - b;
+        (&d * &d).sqrt() < 1e-9
+    }
+
+    #[test]
+    fn focus() {
+        let mut eye = eye();
+        assert!(eye.lens.is_none());
+
+        let mut rng = rand::thread_rng();
+        for &blades in [None, Some(6)].iter() {
+            eye.lens = Some(Lens {
+                radius: 0.5,
+                focus: 10.0,
+                blades: blades,
+            });
+            // all rays through the pixel meet at the focus distance
+            let pinhole = {
+                let ray = eye
+                    .ray(30.0, 10.0, (0.0, 0.0), 64, 48, WaveLength(550.0))
+                    .unwrap();
+                ray.position() + &(ray.direction() * (10.0 / (ray.direction() * &eye.forward)))
+            };
+            for _ in 0..100 {
+                let lens = (rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0));
+                let ray = eye
+                    .ray(30.0, 10.0, lens, 64, 48, WaveLength(550.0))
+                    .unwrap();
+                let offset = ray.position() - &eye.position;
+                assert!(&offset * &offset <= 0.25 + 1e-12);
+
+                let time = (10.0 - &offset * &eye.forward) / (ray.direction() * &eye.forward);
+                let focus = ray.position() + &(ray.direction() * time);
+                assert!(close(&focus, &pinhole));
+            }
+        }
+    }
+
+    #[test]
+    fn look_at() {
+        let description: EyeDescription<f64> = serde_json::from_str(
+            r#"{
+                "from": { "x": 0.0, "y": 0.0, "z": -19.0 },
+                "to": { "x": 0.0, "y": 0.0, "z": 1.0 },
+                "up": { "x": 0.0, "y": 2.0, "z": 0.5 },
+                "fov": 90.0
+            }"#,
+        )
+        .unwrap();
+        let built = description.build(200, 100).unwrap();
+        let expected = eye();
+        assert!(close(&built.position, &expected.position));
+        assert!(close(&built.forward, &expected.forward));
+        assert!(close(&built.right, &expected.right));
+        assert!(close(&built.up, &expected.up));
+        assert!((built.height / built.distance - 2.0).abs() < 1e-9);
+        assert!((built.width / built.height - 2.0).abs() < 1e-9);
+
+        let description: EyeDescription<f64> =
+            serde_json::from_str(include_str!("../../eye.json")).unwrap();
+        assert!(description.build(200, 100).is_ok());
+
+        let misspelled = serde_json::from_str::<EyeDescription<f64>>(
+            r#"{
+                "from": { "x": 0.0, "y": 0.0, "z": -19.0 },
+                "to": { "x": 0.0, "y": 0.0, "z": 1.0 },
+                "up": { "x": 0.0, "y": 2.0, "z": 0.5 },
+                "fv": 90.0
+            }"#,
+        );
+        let message = misspelled.err().unwrap().to_string();
+        assert!(message.contains("look-at eye"), "{}", message);
+        assert!(message.contains("fov"), "{}", message);
+
+        let degenerate = LookAt {
+            from: V3::new(0.0, 0.0, 0.0),
+            to: V3::new(0.0, 3.0, 0.0),
+            up: V3::new(0.0, 1.0, 0.0),
+            fov: 60.0,
+            projection: Projection::Perspective,
+            lens: None,
+        };
+        match EyeDescription::LookAt(degenerate).build(200, 100) {
+            Err(EyeError::Degenerate) => (),
+            _ => panic!("expected degenerate error"),
+        }
+
+        let mut skewed = eye();
+        skewed.up = V3::new(0.0, 1.0, 0.1).normalize();
+        match EyeDescription::Basis(skewed).build(200, 100) {
+            Err(EyeError::NotOrthogonal) => (),
+            _ => panic!("expected orthogonality error"),
+        }
+    }
+
+    #[test]
+    fn projections() {
+        let mut eye = eye();
+        let ray =
+            |eye: &Eye<f64>, x: f64, y: f64| eye.ray(x, y, (0.0, 0.0), 64, 32, WaveLength(550.0));
+
+        eye.projection = Projection::Orthographic;
+        let ray_a = ray(&eye, 0.0, 0.0).unwrap();
+        let ray_b = ray(&eye, 64.0, 32.0).unwrap();
+        assert!(close(ray_a.direction(), &eye.forward));
+        assert!(close(ray_b.direction(), &eye.forward));
+        let d = ray_b.position() - ray_a.position();
+        assert!(close(&d, &V3::new(eye.width, eye.height, 0.0)));
+
+        eye.projection = Projection::Fisheye { fov: 180.0 };
+        assert!(close(
+            ray(&eye, 32.0, 16.0).unwrap().direction(),
+            &eye.forward
+        ));
+        assert!(close(
+            ray(&eye, 64.0, 16.0).unwrap().direction(),
+            &eye.right
+        ));
+        eye.projection = Projection::Fisheye { fov: 30.0 };
+        assert!(ray(&eye, 64.0, 16.0).is_some());
+        assert!(ray(&eye, 64.0, 32.0).is_none());
+        eye.height = eye.width * 20.0;
+        assert!(ray(&eye, 32.0, 0.0).is_none());
+
+        eye.projection = Projection::Equirectangular;
+        assert!(close(
+            ray(&eye, 32.0, 16.0).unwrap().direction(),
+            &eye.forward
+        ));
+        assert!(close(
+            ray(&eye, 0.0, 16.0).unwrap().direction(),
+            &-&eye.forward
+        ));
+        assert!(close(
+            ray(&eye, 48.0, 16.0).unwrap().direction(),
+            &eye.right
+        ));
+        assert!(close(ray(&eye, 32.0, 32.0).unwrap().direction(), &eye.up));
+    }
+}
@@ -0,0 +1,440 @@
+use super::buffer::Buffer;
+use super::color::ColorSpace;
+use super::tone::{Exposure, ToneMapping};
+use super::wave::WaveLengthFactory;
+
+use std::{
+    fs::File,
+    io::{self, Write, BufWriter},
+    path::Path,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// Uncompressed 24 bit TGA.
+    Tga,
+    /// 8 bit RGB PNG, stored without compression.
+    Png,
+    /// Binary 8 bit PPM.
+    Ppm,
+    /// Linear floating point PFM, not clamped.
+    Pfm,
+    /// Linear OpenEXR with the layers chosen by `ExrOptions`.
+    Exr,
+}
+
+impl ImageFormat {
+    /// Recognizes the format by the file extension.
+    pub fn from_path<P>(path: P) -> Option<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let extension = path.as_ref().extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "tga" => Some(ImageFormat::Tga),
+            "png" => Some(ImageFormat::Png),
+            "ppm" => Some(ImageFormat::Ppm),
+            "pfm" => Some(ImageFormat::Pfm),
+            "exr" => Some(ImageFormat::Exr),
+            _ => None,
+        }
+    }
+}
+
+/// How to turn the radiance into the image, the tone mapping is ignored by the floating
+/// point formats, `exr` by all the formats but OpenEXR.
+#[derive(Clone, Default, Debug)]
+pub struct ImageOptions {
+    pub exposure: Exposure,
+    pub tone_mapping: ToneMapping,
+    pub space: ColorSpace,
+    pub exr: ExrOptions,
+}
+
+impl<F> Buffer<F>
+where
+    F: WaveLengthFactory,
+{
+    /// Writes the image file, the format is chosen by the extension of the path.
+    pub fn save<P>(&self, path: P, options: &ImageOptions) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let format = ImageFormat::from_path(path.as_ref()).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "unknown image file extension")
+        })?;
+        self.check_size()?;
+        let mut file = BufWriter::new(File::create(path)?);
+        self.export(&mut file, format, options)?;
+        file.flush()
+    }
+
+    pub fn export<W>(
+        &self,
+        writer: &mut W,
+        format: ImageFormat,
+        options: &ImageOptions,
+    ) -> io::Result<()>
+    where
+        W: Write,
+    {
+        self.check_size()?;
+        let (width, height) = (self.width(), self.height());
+        match format {
+            ImageFormat::Tga => {
+                let mut header = [0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 24, 0];
+                header[12..14].clone_from_slice(&(width as u16).to_le_bytes());
+                header[14..16].clone_from_slice(&(height as u16).to_le_bytes());
+                let mut bytes = vec![0; 3 * width * height];
+                self.write(options, true, bytes.as_mut());
+                writer.write_all(header.as_ref())?;
+                writer.write_all(bytes.as_ref())
+            },
+            ImageFormat::Ppm => {
+                write!(writer, "P6\n{} {}\n255\n", width, height)?;
+                writer.write_all(self.top_down(options).as_ref())
+            },
+            ImageFormat::Png => write_png(writer, width, height, &self.top_down(options)),
+            ImageFormat::Pfm => {
+                // scanlines go from the bottom, as in the buffer, negative scale is little endian
+                write!(writer, "PF\n{} {}\n-1.0\n", width, height)?;
+                let scale = self.exposure(&options.exposure);
+                for index in 0..(width * height) {
+                    let xyz = self.xyz(index) * scale;
+                    let (r, g, b) = options.space.linear(&xyz).tuple(false);
+                    for &c in [r, g, b].iter() {
+                        writer.write_all(&(c as f32).to_le_bytes())?;
+                    }
+                }
+                Ok(())
+            },
+            ImageFormat::Exr => self.export_exr(writer, options),
+        }
+    }
+
+    /// Writes uncompressed scanline OpenEXR of the linear color.
+    /// With the exposure `Exposure::Scale(1.0)` the values are the mean over samples
+    /// and wave lengths.
+    pub fn export_exr<W>(&self, writer: &mut W, options: &ImageOptions) -> io::Result<()>
+    where
+        W: Write,
+    {
+        self.check_size()?;
+        let exr = &options.exr;
+        let (width, height) = (self.width(), self.height());
+        let scale = self.exposure(&options.exposure);
+        let count = (self.sample_count() * self.factory().resolution()) as f64;
+
+        // channels must be sorted by name
+        let mut channels = vec!["B", "G", "R"];
+        if exr.samples {
+            channels.push("samples.Y");
+        }
+        if exr.variance {
+            channels.push("variance.Y");
+        }
+        let (pixel_type, size) = match exr.precision {
+            ExrPrecision::Half => (1u32, 2),
+            ExrPrecision::Float => (2u32, 4),
+        };
+
+        let mut header = Vec::new();
+        let mut attribute = |name: &str, kind: &str, value: &[u8]| {
+            header.extend_from_slice(name.as_bytes());
+            header.push(0);
+            header.extend_from_slice(kind.as_bytes());
+            header.push(0);
+            header.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            header.extend_from_slice(value);
+        };
+
+        let mut list = Vec::new();
+        for channel in &channels {
+            list.extend_from_slice(channel.as_bytes());
+            list.push(0);
+            list.extend_from_slice(&pixel_type.to_le_bytes());
+            // linear flag, three reserved bytes, sampling one by one
+            list.extend_from_slice(&[0, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0]);
+        }
+        list.push(0);
+        attribute("channels", "chlist", list.as_ref());
+        attribute("compression", "compression", &[0]);
+        let mut window = Vec::new();
+        for &v in [0, 0, width as i32 - 1, height as i32 - 1].iter() {
+            window.extend_from_slice(&v.to_le_bytes());
+        }
+        attribute("dataWindow", "box2i", window.as_ref());
+        attribute("displayWindow", "box2i", window.as_ref());
+        attribute("lineOrder", "lineOrder", &[0]);
+        attribute("pixelAspectRatio", "float", &1.0f32.to_le_bytes());
+        attribute("screenWindowCenter", "v2f", &[0; 8]);
+        attribute("screenWindowWidth", "float", &1.0f32.to_le_bytes());
+        header.push(0);
+
+        writer.write_all(&[0x76, 0x2f, 0x31, 0x01, 2, 0, 0, 0])?;
+        writer.write_all(header.as_ref())?;
+
+        // one scanline per block, each block is the line number, the size and the data
+        let line_size = width * channels.len() * size;
+        let mut offset = (8 + header.len() + 8 * height) as u64;
+        for _ in 0..height {
+            writer.write_all(&offset.to_le_bytes())?;
+            offset += (8 + line_size) as u64;
+        }
+
+        let mut line = Vec::with_capacity(line_size);
+        for y in 0..height {
+            // the buffer starts from the bottom row, exr starts from the top
+            let row = height - 1 - y;
+            let pixels = (row * width)..((row + 1) * width);
+            let colors = pixels
+                .clone()
+                .map(|index| options.space.linear(&(self.xyz(index) * scale)))
+                .collect::<Vec<_>>();
+
+            line.clear();
+            let mut put = |value: f64| match exr.precision {
+                ExrPrecision::Half => line.extend_from_slice(&half(value as f32).to_le_bytes()),
+                ExrPrecision::Float => line.extend_from_slice(&(value as f32).to_le_bytes()),
+            };
+            for channel in &channels {
+                match *channel {
+                    "B" => colors.iter().for_each(|c| put(c.tuple(false).2)),
+                    "G" => colors.iter().for_each(|c| put(c.tuple(false).1)),
+                    "R" => colors.iter().for_each(|c| put(c.tuple(false).0)),
+                    "samples.Y" => pixels.clone().for_each(|_| put(count)),
+                    _ => pixels
+                        .clone()
+                        .for_each(|index| put(self.variance(index) * scale * scale)),
+                }
+            }
+
+            writer.write_all(&(y as i32).to_le_bytes())?;
+            writer.write_all(&(line.len() as u32).to_le_bytes())?;
+            writer.write_all(line.as_ref())?;
+        }
+
+        Ok(())
+    }
+
+    // no format has the image without pixels
+    fn check_size(&self) -> io::Result<()> {
+        if self.width() == 0 || self.height() == 0 {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "image is empty",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    // the buffer starts from the bottom row, most formats start from the top
+    fn top_down(&self, options: &ImageOptions) -> Vec<u8> {
+        let row = 3 * self.width();
+        let mut bytes = vec![0; row * self.height()];
+        self.write(options, false, bytes.as_mut());
+        bytes
+            .chunks(row)
+            .rev()
+            .flat_map(|line| line.iter().cloned())
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExrPrecision {
+    Half,
+    Float,
+}
+
+#[derive(Clone, Debug)]
+pub struct ExrOptions {
+    pub precision: ExrPrecision,
+    /// Adds the layer `variance.Y`, the variance of the luminance of one sample.
+    pub variance: bool,
+    /// Adds the layer `samples.Y`, the number of rays traced for the pixel.
+    pub samples: bool,
+}
+
+impl Default for ExrOptions {
+    fn default() -> Self {
+        ExrOptions {
+            precision: ExrPrecision::Float,
+            variance: true,
+            samples: true,
+        }
+    }
+}
+
+// IEEE 754 binary16, rounds to the nearest even
+fn half(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7fffff;
+
+    if exponent == 0xff {
+        let nan = if mantissa != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+
+    let e = exponent - 127 + 15;
+    if e >= 0x1f {
+        sign | 0x7c00
+    } else if e <= 0 {
+        if e < -10 {
+            return sign;
+        }
+        // subnormal, the implicit bit becomes explicit
+        let m = mantissa | 0x800000;
+        let shift = (14 - e) as u32;
+        let h = m >> shift;
+        let rest = m & ((1 << shift) - 1);
+        let halfway = 1 << (shift - 1);
+        let h = if rest > halfway || (rest == halfway && h & 1 == 1) {
+            h + 1
+        } else {
+            h
+        };
+        sign | h as u16
+    } else {
+        let h = ((e as u32) << 10) | (mantissa >> 13);
+        let rest = mantissa & 0x1fff;
+        // the carry may propagate into the exponent, that is correct rounding up
+        let h = if rest > 0x1000 || (rest == 0x1000 && h & 1 == 1) {
+            h + 1
+        } else {
+            h
+        };
+        sign | h as u16
+    }
+}
+
+pub(super) fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                0xedb88320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in bytes {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+fn write_png_chunk<W>(writer: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()>
+where
+    W: Write,
+{
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+    let mut chunk = Vec::with_capacity(4 + data.len());
+    chunk.extend_from_slice(kind.as_ref());
+    chunk.extend_from_slice(data);
+    writer.write_all(chunk.as_ref())?;
+    writer.write_all(&crc32(chunk.as_ref()).to_be_bytes())
+}
+
+fn write_png<W>(writer: &mut W, width: usize, height: usize, rgb: &[u8]) -> io::Result<()>
+where
+    W: Write,
+{
+    writer.write_all(b"\x89PNG\r\n\x1a\n")?;
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(width as u32).to_be_bytes());
+    header.extend_from_slice(&(height as u32).to_be_bytes());
+    // bit depth 8, color type rgb, deflate, adaptive filtering, no interlace
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+    write_png_chunk(writer, b"IHDR", header.as_ref())?;
+
+    // every scanline is prefixed by the filter type, zero is none
+    let mut raw = Vec::with_capacity((3 * width + 1) * height);
+    for line in rgb.chunks(3 * width) {
+        raw.push(0);
+        raw.extend_from_slice(line);
+    }
+
+    // zlib stream of stored deflate blocks
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = raw.chunks(0xffff).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        zlib.push(if last { 1 } else { 0 });
+        zlib.extend_from_slice(&(block.len() as u16).to_le_bytes());
+        zlib.extend_from_slice(&(!(block.len() as u16)).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(raw.as_ref()).to_be_bytes());
+    write_png_chunk(writer, b"IDAT", zlib.as_ref())?;
+
+    write_png_chunk(writer, b"IEND", &[])
+}
+
+#[cfg(test)]
+mod test {
+    use super::{crc32, adler32, half, ImageFormat, ImageOptions};
+    use crate::core::{Buffer, WaveLengthTrimmedFactory};
+    use std::io;
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae426082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e60398);
+    }
+
+    #[test]
+    fn half_float() {
+        assert_eq!(half(0.0), 0x0000);
+        assert_eq!(half(1.0), 0x3c00);
+        assert_eq!(half(-2.0), 0xc000);
+        assert_eq!(half(0.1), 0x2e66);
+        assert_eq!(half(65504.0), 0x7bff);
+        assert_eq!(half(1e6), 0x7c00);
+        assert_eq!(half(1e-7), 0x0002);
+        assert_eq!(half(f32::NAN) & 0x7e00, 0x7e00);
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(ImageFormat::from_path("a/b.PNG"), Some(ImageFormat::Png));
+        assert_eq!(ImageFormat::from_path("b.pfm"), Some(ImageFormat::Pfm));
+        assert_eq!(ImageFormat::from_path("b.jpg"), None);
+        assert_eq!(ImageFormat::from_path("b"), None);
+    }
+
+    #[test]
+    fn empty() {
+        let formats = [
+            ImageFormat::Tga,
+            ImageFormat::Ppm,
+            ImageFormat::Png,
+            ImageFormat::Pfm,
+            ImageFormat::Exr,
+        ];
+        for &(width, height) in [(0, 2), (2, 0)].iter() {
+            let buffer = Buffer::new(width, height, None, WaveLengthTrimmedFactory);
+            for format in formats.iter() {
+                let mut bytes = Vec::new();
+                let result = buffer.export(&mut bytes, *format, &ImageOptions::default());
+                assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+                assert!(bytes.is_empty());
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use super::color::Xyz;
+use super::wave::WaveLength;
+use super::spectrum::{SpectrumError, parse_csv};
+use super::path::resolve_path;
+
+use std::{
+    cmp::Ordering,
+    convert::TryFrom,
+    fs,
+    path::{Path, PathBuf},
+};
+use serde::{Serialize, Deserialize};
+
+/// Response of the observer to the unit of energy at the wave length, the color the buffer
+/// accumulates. It is the CIE 1964 10° standard observer by default, the CIE 1931 2° one
+/// or the response of the camera sensor can be loaded from the csv file.
+///
+/// In the scene file it is `"Cie1964"`, `{ "Csv": "file.csv" }` relative to the scene file
+/// with the wave length and the three responses in the columns,
+/// or `{ "Table": [[wave length, x, y, z], ...] }`.
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(
+    try_from = "ColorMatchingDescription",
+    into = "ColorMatchingDescription"
+)]
+pub enum ColorMatching {
+    #[default]
+    Cie1964,
+    /// Sorted by the wave length, linear between the rows and zero outside.
+    Table(Vec<(f64, Xyz)>),
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum ColorMatchingDescription {
+    Cie1964,
+    Csv(PathBuf),
+    Table(Vec<(f64, f64, f64, f64)>),
+}
+
+impl TryFrom<ColorMatchingDescription> for ColorMatching {
+    type Error = SpectrumError;
+
+    fn try_from(description: ColorMatchingDescription) -> Result<Self, Self::Error> {
+        match description {
+            ColorMatchingDescription::Cie1964 => Ok(ColorMatching::Cie1964),
+            ColorMatchingDescription::Csv(path) => ColorMatching::load_csv(resolve_path(path)),
+            ColorMatchingDescription::Table(rows) => ColorMatching::new(
+                rows.into_iter()
+                    .map(|(l, x, y, z)| (l, Xyz::new(x, y, z)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<ColorMatching> for ColorMatchingDescription {
+    fn from(matching: ColorMatching) -> Self {
+        match matching {
+            ColorMatching::Cie1964 => ColorMatchingDescription::Cie1964,
+            ColorMatching::Table(rows) => ColorMatchingDescription::Table(
+                rows.into_iter()
+                    .map(|(l, xyz)| {
+                        let (x, y, z) = xyz.tuple();
+                        (l, x, y, z)
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl ColorMatching {
+    /// The rows are `(wave length in nanometers, response)` in any order.
+    pub fn new(rows: Vec<(f64, Xyz)>) -> Result<Self, SpectrumError> {
+        let mut rows = rows;
+        if rows.is_empty() {
+            return Err(SpectrumError::Empty);
+        }
+        rows.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        Ok(ColorMatching::Table(rows))
+    }
+
+    pub fn load_csv<P>(path: P) -> Result<Self, SpectrumError>
+    where
+        P: AsRef<Path>,
+    {
+        let text = fs::read_to_string(path)?;
+        Self::parse_csv(text.as_str())
+    }
+
+    /// Four columns, the wave length and the three responses, like `ciexyz64_1.csv`.
+    pub fn parse_csv(text: &str) -> Result<Self, SpectrumError> {
+        let rows = parse_csv(text)?
+            .into_iter()
+            .map(|(line, row)| match row.as_slice() {
+                &[l, x, y, z, ..] => Ok((l, Xyz::new(x, y, z))),
+                _ => Err(SpectrumError::MissingColumn { line: line }),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::new(rows)
+    }
+
+    /// `cie1964` or the hash of the table, FNV-1a, to tell the observers apart in the state file.
+    #[allow(clippy::needless_borrowed_reference)]
+    pub fn identity(&self) -> String {
+        match self {
+            &ColorMatching::Cie1964 => "cie1964".to_owned(),
+            &ColorMatching::Table(ref rows) => {
+                let mut hash = 0xcbf29ce484222325u64;
+                for &(l, ref xyz) in rows.iter() {
+                    let (x, y, z) = xyz.tuple();
+                    for byte in [l, x, y, z].iter().flat_map(|v| v.to_le_bytes().to_vec()) {
+                        hash ^= byte as u64;
+                        hash = hash.wrapping_mul(0x100000001b3);
+                    }
+                }
+                format!("table-{:016x}", hash)
+            },
+        }
+    }
+
+    #[allow(clippy::needless_borrowed_reference)]
+    pub fn xyz(&self, wave_length: &WaveLength) -> Xyz {
+        match self {
+            &ColorMatching::Cie1964 => wave_length.xyz(),
+            &ColorMatching::Table(ref rows) => {
+                let l = wave_length.0;
+                let i = rows.partition_point(|&(x, _)| x < l);
+                if i == rows.len() || (i == 0 && rows[0].0 != l) {
+                    Xyz::default()
+                } else if rows[i].0 == l {
+                    rows[i].1.clone()
+                } else {
+                    let (x0, ref c0) = rows[i - 1];
+                    let (x1, ref c1) = rows[i];
+                    let s = (l - x0) / (x1 - x0);
+                    c0.clone() * (1.0 - s) + c1.clone() * s
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ColorMatching;
+    use crate::core::{WaveLength, with_base_directory};
+
+    #[test]
+    fn shipped_table() {
+        let loaded = ColorMatching::load_csv("ciexyz64_1.csv").unwrap();
+        for &l in [360.0, 455.5, 555.0, 700.25, 830.0].iter() {
+            let (x0, y0, z0) = ColorMatching::Cie1964.xyz(&WaveLength(l)).tuple();
+            let (x1, y1, z1) = loaded.xyz(&WaveLength(l)).tuple();
+            assert!((x0 - x1).abs() + (y0 - y1).abs() + (z0 - z1).abs() < 1e-12);
+        }
+        assert_eq!(loaded.xyz(&WaveLength(300.0)).tuple(), (0.0, 0.0, 0.0));
+        assert_eq!(loaded.xyz(&WaveLength(900.0)).tuple(), (0.0, 0.0, 0.0));
+
+        let json = "{\"Csv\": \"ciexyz64_1.csv\"}";
+        let relative: ColorMatching =
+            with_base_directory(env!("CARGO_MANIFEST_DIR"), || serde_json::from_str(json)).unwrap();
+        assert!(relative.xyz(&WaveLength(555.0)).tuple().1 > 0.99);
+        assert!(
+            with_base_directory("missing", || serde_json::from_str::<ColorMatching>(json)).is_err()
+        );
+    }
+
+    #[test]
+    fn sensor() {
+        let sensor: ColorMatching =
+            serde_json::from_str("{\"Table\": [[600, 1, 0, 0], [500, 0, 1, 0], [400, 0, 0, 1]]}")
+                .unwrap();
+        let (x, y, z) = sensor.xyz(&WaveLength(450.0)).tuple();
+        assert!((x - 0.0).abs() < 1e-12 && (y - 0.5).abs() < 1e-12 && (z - 0.5).abs() < 1e-12);
+        assert!(ColorMatching::parse_csv("400, 0.1, 0.2\n").is_err());
+    }
+}
@@ -0,0 +1,97 @@
+use super::algebra::V3;
+use super::spectrum::Spectrum;
+use super::wave::WaveLength;
+
+use serde::{Serialize, Deserialize};
+use num::Float;
+
+/// Homogeneous participating medium, the coefficients are per unit of the distance.
+///
+/// The scattered photon goes by the Henyey-Greenstein phase function, the `asymmetry`
+/// is the mean cosine of the scattering angle, positive scatters forward,
+/// zero is isotropic.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Medium {
+    pub absorption: Spectrum,
+    pub scattering: Spectrum,
+    #[serde(default)]
+    pub asymmetry: f64,
+}
+
+impl Medium {
+    pub fn extinction(&self, wave_length: &WaveLength) -> f64 {
+        self.absorption.value(wave_length) + self.scattering.value(wave_length)
+    }
+
+    /// Probability of the collision to be the scattering rather than the absorption.
+    pub fn albedo(&self, wave_length: &WaveLength) -> f64 {
+        let extinction = self.extinction(wave_length);
+        if extinction > 0.0 {
+            self.scattering.value(wave_length) / extinction
+        } else {
+            0.0
+        }
+    }
+
+    /// Probability of the photon to pass the distance without the collision.
+    pub fn transmittance(&self, wave_length: &WaveLength, distance: f64) -> f64 {
+        (-self.extinction(wave_length) * distance).exp()
+    }
+
+    /// New direction of the scattered photon, `u` and `v` are uniform in `[0, 1)`.
+    pub fn scatter<C>(&self, direction: &V3<C>, u: f64, v: f64) -> V3<C>
+    where
+        C: Float,
+    {
+        use std::f64::consts::TAU;
+
+        let g = self.asymmetry;
+        let cos = if g.abs() < 1e-3 {
+            1.0 - 2.0 * u
+        } else {
+            let s = (1.0 - g * g) / (1.0 - g + 2.0 * g * u);
+            ((1.0 + g * g - s * s) / (2.0 * g)).clamp(-1.0, 1.0)
+        };
+        let sin = (1.0 - cos * cos).max(0.0).sqrt();
+        let phi = TAU * v;
+
+        let (s, t) = direction.frame();
+        let c = |x: f64| C::from(x).unwrap();
+        &(&(&s * c(sin * phi.cos())) + &(&t * c(sin * phi.sin()))) + &(direction * c(cos))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Medium;
+    use crate::core::{V3, Spectrum};
+
+    #[test]
+    fn henyey_greenstein() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        let direction: V3<f64> = V3::new(0.0, 0.0, 1.0);
+        for &g in [-0.5, 0.0, 0.8].iter() {
+            let medium = Medium {
+                absorption: Spectrum::constant(0.0),
+                scattering: Spectrum::constant(1.0),
+                asymmetry: g,
+            };
+            let n = 20000;
+            let mean = (0..n)
+                .map(|_| {
+                    let d = medium.scatter(
+                        &direction,
+                        rng.gen_range(0.0..1.0),
+                        rng.gen_range(0.0..1.0),
+                    );
+                    assert!(((&d * &d) - 1.0).abs() < 1e-9);
+                    d.z()
+                })
+                .sum::<f64>()
+                / (n as f64);
+            assert!((mean - g).abs() < 0.02);
+        }
+    }
+}
@@ -1,14 +1,34 @@
 mod algebra;
 mod ray;
 mod scene;
+mod color;
+mod tone;
 mod wave;
+mod spectrum;
+mod medium;
+mod matching;
+mod path;
+mod eye;
 mod buffer;
+mod image;
+mod state;
 mod worker;
 
-pub use self::scene::{Scene, Side, Intersect, Event, Material};
-pub use self::ray::Ray;
+pub use self::scene::{Scene, Side, Intersect, Event, Material, Light};
+pub use self::ray::{Ray, Tracing};
 pub use self::algebra::V3;
+pub use self::color::{Xyz, Rgb, ColorSpace};
+pub use self::tone::{Exposure, ToneMapping};
 pub use self::wave::{
-    Rgb, WaveLength, WaveLengthFactory, WaveLengthLinearFactory, WaveLengthTrimmedFactory,
+    WaveLength, WaveLengthFactory, WaveLengthLinearFactory, WaveLengthTrimmedFactory,
+    WaveLengthUniformFactory, WaveLengthStratifiedFactory, WaveLengthImportanceFactory,
+    WaveLengthSampling, WaveLengthAnyFactory, WaveLengthAny,
 };
-pub use self::buffer::{Buffer, Eye, Progress, Report};
+pub use self::eye::{Eye, EyeDescription, EyeError, LookAt, Lens, Projection};
+pub use self::spectrum::{Spectrum, SpectrumDescription, SpectrumError};
+pub use self::medium::Medium;
+pub use self::matching::{ColorMatching, ColorMatchingDescription};
+pub use self::buffer::{Buffer, Progress, Report};
+pub use self::image::{ImageFormat, ImageOptions, ExrOptions, ExrPrecision};
+pub use self::state::{State, StateError, scene_hash};
+pub use self::path::{with_base_directory, resolve_path};
@@ -0,0 +1,54 @@
+use std::{
+    cell::RefCell,
+    path::{Path, PathBuf},
+};
+
+thread_local! {
+    static BASE_DIRECTORY: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// Runs the function with the relative paths resolved against the directory,
+/// the scene file is parsed this way so its paths don't depend on the working directory.
+pub fn with_base_directory<P, F, T>(directory: P, f: F) -> T
+where
+    P: AsRef<Path>,
+    F: FnOnce() -> T,
+{
+    let directory = directory.as_ref().to_path_buf();
+    let previous = BASE_DIRECTORY.with(|base| base.replace(Some(directory)));
+    let result = f();
+    BASE_DIRECTORY.with(|base| base.replace(previous));
+    result
+}
+
+/// The path joined to the base directory if it is relative and the base is set.
+pub fn resolve_path<P>(path: P) -> PathBuf
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    BASE_DIRECTORY.with(|base| match base.borrow().as_ref() {
+        Some(base) if path.is_relative() => base.join(path),
+        _ => path.to_path_buf(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{with_base_directory, resolve_path};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn resolve() {
+        assert_eq!(resolve_path("a.csv"), PathBuf::from("a.csv"));
+        with_base_directory("scenes", || {
+            assert_eq!(resolve_path("a.csv"), Path::new("scenes").join("a.csv"));
+            with_base_directory("", || {
+                assert_eq!(resolve_path("a.csv"), PathBuf::from("a.csv"))
+            });
+            let absolute = std::env::temp_dir().join("a.csv");
+            assert_eq!(resolve_path(&absolute), absolute);
+        });
+        assert_eq!(resolve_path("a.csv"), PathBuf::from("a.csv"));
+    }
+}
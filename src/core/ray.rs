@@ -1,11 +1,47 @@
 use super::algebra::V3;
-use super::scene::{Scene, Event, Material};
-use super::wave::WaveLength;
+use super::scene::{Scene, Event, Material, Intersect, Light};
+use super::medium::Medium;
+use super::matching::ColorMatching;
+use super::wave::{WaveLength, WaveLengthSampling};
 
 use serde::{Serialize, Deserialize};
 use num::Float;
 use rand::Rng;
 
+/// How deep the paths are traced.
+///
+/// Past `min_depth` the path continues with the probability of its throughput,
+/// clamped to `survival`, and the survived paths are scaled up, so the estimate stays unbiased.
+/// The materials choose their events with the probability of the reflectance,
+/// the throughput stays one then, and it is the `survival` which terminates the paths.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Tracing {
+    pub min_depth: usize,
+    /// Hard limit, the paths cut here bias the result.
+    pub max_depth: usize,
+    pub survival: f64,
+    /// Fills the space outside of the surfaces, the vacuum by default.
+    pub medium: Option<Medium>,
+    /// The color of the photon reaching the eye.
+    pub observer: ColorMatching,
+    /// The wave lengths of each sample of the pixel, the importance follows the observer.
+    pub wave_lengths: WaveLengthSampling,
+}
+
+impl Default for Tracing {
+    fn default() -> Self {
+        Tracing {
+            min_depth: 3,
+            max_depth: 64,
+            survival: 0.9,
+            medium: None,
+            observer: ColorMatching::default(),
+            wave_lengths: WaveLengthSampling::default(),
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Ray<C>
 where
@@ -36,50 +72,284 @@ where
         &self.direction
     }
 
-    pub fn trace<S, R>(&self, scene: &S, rng: &mut R) -> f64
+    /// Photon value along the ray, the diffuse vertices also sample the `lights` directly,
+    /// which should be the lights of the scene.
+    pub fn trace<S, R>(&self, scene: &S, lights: &[Light<C>], tracing: &Tracing, rng: &mut R) -> f64
     where
         S: Scene<C>,
         R: Rng,
     {
-        self.trace_inner(scene, rng, 0)
+        let path = Path {
+            scene: scene,
+            lights: lights,
+            tracing: tracing,
+        };
+        self.trace_inner(&path, &[], rng, 0, 1.0, false)
     }
 
-    fn trace_inner<S, R>(&self, scene: &S, rng: &mut R, level: usize) -> f64
+    /// The `media` are those the path has entered through the refracting surfaces,
+    /// the innermost last, `None` for the interior without the medium. The medium
+    /// of the `tracing` fills the space outside of all of them.
+    fn trace_inner<'a, S, R>(
+        &self,
+        path: &Path<'a, S, C>,
+        media: &[Option<&'a Medium>],
+        rng: &mut R,
+        level: usize,
+        throughput: f64,
+        diffuse: bool,
+    ) -> f64
     where
         S: Scene<C>,
         R: Rng,
     {
         use std::f64::consts::TAU;
 
-        let max_level = 7;
-        if level > max_level {
+        let &Path {
+            scene: scene,
+            lights: lights,
+            tracing: tracing,
+        } = path;
+
+        if level > tracing.max_depth {
             return 0.0;
         };
+        let survival = if level > tracing.min_depth {
+            throughput.min(tracing.survival)
+        } else {
+            1.0
+        };
+        if survival < 1.0 && rng.gen_range(0.0..1.0) >= survival {
+            return 0.0;
+        }
+        let throughput = throughput / survival;
+
+        let hit = scene.find_intersect(self);
+        let medium = match (media.last(), &hit) {
+            (Some(&medium), _) => medium,
+            // the path has started in the interior, like the eye in the smoke
+            (None, Some(result)) if !result.side.outer() => result.material.medium(),
+            (None, _) => tracing.medium.as_ref(),
+        };
+        let flight = match medium {
+            Some(medium) => self.fly(medium, hit.as_ref().map(|result| &result.position), rng),
+            None => Flight::Through,
+        };
 
-        match scene.find_intersect(self) {
-            Some(result) => {
+        let photon = match (flight, hit) {
+            (Flight::Absorbed, _) => 0.0,
+            (Flight::Scattered(ray), _) => {
+                ray.trace_inner(path, media, rng, level + 1, throughput, false)
+            },
+            (Flight::Through, Some(ref result)) if self.absorbed(result, rng) => 0.0,
+            (Flight::Through, Some(result)) => {
                 let emission = rng.gen_range(0.0..1.0);
                 let event = rng.gen_range(0.0..1.0);
-                let fate = result
-                    .material
-                    .fate(&self.wave_length, result.side, emission, event);
+                let cosine = (&self.direction * &result.normal).abs();
+                let fate =
+                    result
+                        .material
+                        .fate(&self.wave_length, result.side, cosine, emission, event);
                 let a = C::from(rng.gen_range(0.0f64..TAU)).unwrap();
                 let z = C::from(rng.gen_range(-1.0f64..1.0f64)).unwrap();
                 match fate {
-                    Event::Emission(d) => d,
+                    Event::Emission(d) => {
+                        if diffuse {
+                            // the light could be sampled directly from the previous vertex
+                            let light_pdf = lights
+                                .iter()
+                                .find(|light| light.contains(&result.position))
+                                .map(|light| light.pdf(&self.position))
+                                .unwrap_or_else(C::zero);
+                            d * Self::weight(light_pdf, lights.len())
+                        } else {
+                            d
+                        }
+                    },
                     Event::Decay => 0.0,
-                    Event::Diffuse => self
-                        .diffuse(&result.position, &result.normal, a, z)
-                        .trace_inner(scene, rng, level + 1),
-                    Event::Reflect(factor) => self
-                        .reflect(&result.position, &result.normal, factor, a, z)
-                        .trace_inner(scene, rng, level + 1),
-                    Event::Refract(factor) => self
-                        .refract(&result.position, &result.normal, factor)
-                        .trace_inner(scene, rng, level + 1),
+                    Event::Diffuse => {
+                        let direct = self.direct(path, medium, rng, &result);
+                        let indirect = self
+                            .diffuse(&result.position, &result.normal, a, z)
+                            .trace_inner(path, media, rng, level + 1, throughput, true);
+                        direct + indirect
+                    },
+                    Event::Fluorescence(wave_length) => {
+                        let ray = Ray {
+                            position: self.position.clone(),
+                            direction: self.direction.clone(),
+                            wave_length: wave_length,
+                        };
+                        let direct = ray.direct(path, medium, rng, &result);
+                        let indirect = ray
+                            .diffuse(&result.position, &result.normal, a, z)
+                            .trace_inner(path, media, rng, level + 1, throughput, true);
+                        direct + indirect
+                    },
+                    Event::Reflect(factor) => {
+                        match self.reflect(&result.position, &result.normal, factor, a, z) {
+                            Some((ray, weight)) => {
+                                let weight = weight.to_f64().unwrap();
+                                weight
+                                    * ray.trace_inner(
+                                        path,
+                                        media,
+                                        rng,
+                                        level + 1,
+                                        throughput * weight,
+                                        false,
+                                    )
+                            },
+                            None => 0.0,
+                        }
+                    },
+                    Event::Refract(factor) => {
+                        let ray = self.refract(&result.position, &result.normal, factor);
+                        // unless totally reflected the ray enters or leaves the interior
+                        let mut media = media.to_vec();
+                        if &ray.direction * &result.normal < C::zero() {
+                            if result.side.outer() {
+                                media.push(result.material.medium());
+                            } else {
+                                media.pop();
+                            }
+                        }
+                        ray.trace_inner(path, media.as_ref(), rng, level + 1, throughput, false)
+                    },
                 }
             },
-            None => 0.0,
+            (Flight::Through, None) => 0.0,
+        };
+        photon / survival
+    }
+
+    /// Balance heuristic weight of the diffuse direction against the light sampling,
+    /// divided by the density of the diffuse direction relative to the light sampling,
+    /// so it is the factor of the emission for both of the strategies.
+    fn weight(light_pdf: C, count: usize) -> f64 {
+        use std::f64::consts::TAU;
+
+        if count == 0 {
+            return 1.0;
+        }
+        let light_pdf = light_pdf.to_f64().unwrap() / (count as f64);
+        let diffuse_pdf = 1.0 / TAU;
+        diffuse_pdf / (diffuse_pdf + light_pdf)
+    }
+
+    /// Whether the photon leaving the interior of the material has been absorbed there,
+    /// it has been inside since the previous vertex.
+    fn absorbed<M, R>(&self, result: &Intersect<'_, M, C>, rng: &mut R) -> bool
+    where
+        M: Material<C>,
+        R: Rng,
+    {
+        if result.side.outer() {
+            return false;
+        }
+        let absorption = result.material.absorption(&self.wave_length);
+        if absorption <= 0.0 {
+            return false;
+        }
+        let d = &result.position - &self.position;
+        let distance = (&d * &d).sqrt().to_f64().unwrap();
+        rng.gen_range(0.0..1.0) >= (-absorption * distance).exp()
+    }
+
+    /// Free flight through the medium toward the hit position, or to the infinity.
+    fn fly<R>(&self, medium: &Medium, hit: Option<&V3<C>>, rng: &mut R) -> Flight<C>
+    where
+        R: Rng,
+    {
+        let extinction = medium.extinction(&self.wave_length);
+        if extinction <= 0.0 {
+            return Flight::Through;
+        }
+        let time = -(1.0 - rng.gen_range(0.0..1.0f64)).ln() / extinction;
+        let distance = hit
+            .map(|position| {
+                let d = position - &self.position;
+                (&d * &d).sqrt().to_f64().unwrap()
+            })
+            .unwrap_or(f64::INFINITY);
+        if time >= distance {
+            return Flight::Through;
+        }
+        if rng.gen_range(0.0..1.0) >= medium.albedo(&self.wave_length) {
+            return Flight::Absorbed;
+        }
+
+        let position = &self.position + &(&self.direction * C::from(time).unwrap());
+        let u = rng.gen_range(0.0..1.0);
+        let v = rng.gen_range(0.0..1.0);
+        Flight::Scattered(Ray {
+            position: position,
+            direction: medium.scatter(&self.direction, u, v),
+            wave_length: self.wave_length.clone(),
+        })
+    }
+
+    /// Next event estimation, samples the direction toward the random light
+    /// and casts the shadow ray.
+    /// The shadow ray is attenuated by the medium around the vertex.
+    fn direct<S, R>(
+        &self,
+        path: &Path<'_, S, C>,
+        medium: Option<&Medium>,
+        rng: &mut R,
+        vertex: &Intersect<'_, S::Material, C>,
+    ) -> f64
+    where
+        S: Scene<C>,
+        R: Rng,
+    {
+        let &Path {
+            scene: scene,
+            lights: lights,
+            ..
+        } = path;
+
+        if lights.is_empty() {
+            return 0.0;
+        }
+
+        let light = &lights[rng.gen_range(0..lights.len())];
+        let u = C::from(rng.gen_range(0.0f64..1.0)).unwrap();
+        let v = C::from(rng.gen_range(0.0f64..1.0)).unwrap();
+        let direction = match light.sample(&vertex.position, u, v) {
+            Some(direction) if &direction * &vertex.normal > C::zero() => direction,
+            _ => return 0.0,
+        };
+        let shadow = Ray {
+            position: &vertex.position + &(&direction * C::epsilon()),
+            direction: direction,
+            wave_length: self.wave_length.clone(),
+        };
+
+        match scene.find_intersect(&shadow) {
+            Some(result) if light.contains(&result.position) => {
+                let emission = rng.gen_range(0.0..1.0);
+                let event = rng.gen_range(0.0..1.0);
+                let cosine = (&shadow.direction * &result.normal).abs();
+                match result
+                    .material
+                    .fate(&self.wave_length, result.side, cosine, emission, event)
+                {
+                    Event::Emission(d) => {
+                        let transmittance = match medium {
+                            Some(medium) => {
+                                let d = &result.position - &vertex.position;
+                                let distance = (&d * &d).sqrt().to_f64().unwrap();
+                                medium.transmittance(&self.wave_length, distance)
+                            },
+                            None => 1.0,
+                        };
+                        d * transmittance * Self::weight(light.pdf(&vertex.position), lights.len())
+                    },
+                    _ => 0.0,
+                }
+            },
+            _ => 0.0,
         }
     }
 
@@ -98,33 +368,395 @@ where
         }
     }
 
-    fn reflect(&self, position: &V3<C>, normal: &V3<C>, factor: C, a: C, z: C) -> Self {
+    /// Reflection on the GGX microfacets, `factor` is the roughness, zero for the mirror.
+    ///
+    /// The half vector is sampled proportionally to its distribution times its cosine,
+    /// `a` is the azimuth and `z` is uniform in `[-1, 1)`. Returns the weight of the ray,
+    /// the masking and shadowing over the density, `None` if the ray goes under the surface.
+    fn reflect(
+        &self,
+        position: &V3<C>,
+        normal: &V3<C>,
+        factor: C,
+        a: C,
+        z: C,
+    ) -> Option<(Self, C)> {
+        let one = C::one();
+        let two = C::from(2.0).unwrap();
+
         let incident = &self.direction;
-        let dot_product = incident * normal;
-        let direction = &(normal * (C::from(-2.0).unwrap() * dot_product)) + incident;
-        let _ = (factor, a, z);
+        let half = if factor > C::zero() {
+            let u = (z + one) / two;
+            let tan_sq = factor * factor * u / (one - u);
+            let cos = (one / (one + tan_sq)).sqrt();
+            let sin = (one - cos * cos).max(C::zero()).sqrt();
+            let (s, t) = normal.frame();
+            &(&(&s * (sin * a.cos())) + &(&t * (sin * a.sin()))) + &(normal * cos)
+        } else {
+            normal.clone()
+        };
+        let dot_product = incident * &half;
+        let direction = &(&half * (-two * dot_product)) + incident;
 
-        Ray {
+        let cos_i = -(incident * normal);
+        let cos_o = &direction * normal;
+        if dot_product >= C::zero() || cos_o <= C::zero() {
+            return None;
+        }
+        let weight = if factor > C::zero() {
+            let g = smith(factor, cos_i) * smith(factor, cos_o);
+            g * (&direction * &half) / (cos_i * (&half * normal))
+        } else {
+            one
+        };
+
+        let ray = Ray {
             position: position + &(&direction * C::epsilon()),
             direction: direction,
             wave_length: self.wave_length.clone(),
-        }
+        };
+        Some((ray, weight))
     }
 
+    /// Snell's law, the normal looks against the incident ray.
     fn refract(&self, position: &V3<C>, normal: &V3<C>, factor: C) -> Self {
         let incident = &self.direction;
-        let temp = incident.cross(normal).cross(normal);
-        let sin_b_sq = (&temp * &temp) * (factor * factor);
+        // tangential component of the incident direction
+        let tangent = incident - &(normal * (incident * normal));
+        let sin_b_sq = (&tangent * &tangent) * (factor * factor);
         if sin_b_sq < C::one() {
             let cos_b = (C::one() - sin_b_sq).sqrt();
-            let direction = &(&temp * factor) - &(normal * cos_b);
+            let direction = &(&tangent * factor) - &(normal * cos_b);
             Ray {
                 position: position + &(&direction * C::epsilon()),
                 direction: direction,
                 wave_length: self.wave_length.clone(),
             }
         } else {
-            self.reflect(position, normal, C::one(), C::zero(), C::zero())
+            // total internal reflection
+            let direction = &(normal * (C::from(-2.0).unwrap() * (incident * normal))) + incident;
+            Ray {
+                position: position + &(&direction * C::epsilon()),
+                direction: direction,
+                wave_length: self.wave_length.clone(),
+            }
         }
     }
 }
+
+/// Smith masking function of the GGX distribution with the roughness `alpha`.
+fn smith<C>(alpha: C, cosine: C) -> C
+where
+    C: Float,
+{
+    let one = C::one();
+    let tan_sq = (one - cosine * cosine).max(C::zero()) / (cosine * cosine);
+    C::from(2.0).unwrap() / (one + (one + alpha * alpha * tan_sq).sqrt())
+}
+
+/// Where the photon flying through the medium ends.
+enum Flight<C>
+where
+    C: Float,
+{
+    Through,
+    Absorbed,
+    Scattered(Ray<C>),
+}
+
+/// What stays the same along the path.
+struct Path<'a, S, C>
+where
+    S: Scene<C>,
+    C: Float,
+{
+    scene: &'a S,
+    lights: &'a [Light<C>],
+    tracing: &'a Tracing,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Ray, Tracing};
+    use crate::{
+        core::{V3, Scene, WaveLength, Light, Spectrum, Medium},
+        tree::{Primitive, Sphere, Plane, AxisAlignedBox},
+        light::{CustomMaterial, Dispersion, Illuminant},
+    };
+
+    /// Mean of the samples and its squared standard error.
+    fn estimate<S>(
+        scene: &S,
+        ray: &Ray<f64>,
+        lights: &[Light<f64>],
+        tracing: &Tracing,
+        n: usize,
+    ) -> (f64, f64)
+    where
+        S: Scene<f64>,
+    {
+        let mut rng = rand::thread_rng();
+        let (sum, squares) = (0..n)
+            .map(|_| ray.trace(scene, lights, tracing, &mut rng))
+            .fold((0.0, 0.0), |(sum, squares), x| (sum + x, squares + x * x));
+        let mean = sum / (n as f64);
+        let variance = squares / (n as f64) - mean * mean;
+        (mean, variance / (n as f64))
+    }
+
+    fn light() -> Primitive<CustomMaterial, f64> {
+        Primitive::Sphere(Sphere::new(
+            V3::new(0.0, 2.0, 0.0),
+            0.25,
+            CustomMaterial::Light {
+                emission: Illuminant::Blackbody(6000.0).into(),
+                intensity: 3.5,
+            },
+        ))
+    }
+
+    #[test]
+    fn direct_light() {
+        let scene: Vec<Primitive<CustomMaterial, f64>> = vec![
+            Primitive::Plane(Plane::new(
+                V3::new(0.0, 0.0, 0.0),
+                V3::new(0.0, 1.0, 0.0),
+                CustomMaterial::DiffuseWhite,
+            )),
+            light(),
+        ];
+        let lights = scene.lights();
+        assert_eq!(lights.len(), 1);
+
+        let ray = Ray::new(
+            V3::new(0.0, 1.0, -3.0),
+            V3::new(0.0, -1.0, 3.0).normalize(),
+            WaveLength(550.0),
+        );
+        let tracing = Tracing::default();
+        let (path, path_error) = estimate(&scene, &ray, &[], &tracing, 50000);
+        let (direct, direct_error) = estimate(&scene, &ray, lights.as_ref(), &tracing, 50000);
+        assert!(direct_error < path_error);
+        assert!((path - direct).abs() < 5.0 * (path_error + direct_error).sqrt());
+    }
+
+    #[test]
+    fn roulette() {
+        // closed white room, the paths end only by the depth limit or the roulette
+        let scene: Vec<Primitive<CustomMaterial, f64>> = vec![
+            Primitive::AxisAlignedBox(AxisAlignedBox::new(
+                V3::new(-4.0, -4.0, -4.0),
+                V3::new(4.0, 4.0, 4.0),
+                CustomMaterial::DiffuseWhite,
+            )),
+            light(),
+        ];
+        let lights = scene.lights();
+        let ray = Ray::new(
+            V3::new(0.0, 0.0, -3.0),
+            V3::new(0.0, 0.0, 1.0),
+            WaveLength(550.0),
+        );
+
+        let tracing = Tracing {
+            min_depth: 0,
+            max_depth: 8,
+            survival: 1.0,
+            ..Tracing::default()
+        };
+        let (full, full_error) = estimate(&scene, &ray, lights.as_ref(), &tracing, 10000);
+        let tracing = Tracing {
+            survival: 0.8,
+            ..tracing
+        };
+        let (roulette, roulette_error) = estimate(&scene, &ray, lights.as_ref(), &tracing, 10000);
+        assert!((full - roulette).abs() < 5.0 * (full_error + roulette_error).sqrt());
+    }
+
+    #[test]
+    fn refract() {
+        let n = 1.5;
+        let normal = V3::new(0.0, 1.0, 0.0);
+        let ray = Ray::new(
+            V3::new(-1.0, 1.0, 0.0),
+            V3::new(1.0, -1.0, 0.0).normalize(),
+            WaveLength(550.0),
+        );
+        let inside = ray.refract(&V3::new(0.0, 0.0, 0.0), &normal, 1.0 / n);
+        let d = inside.direction();
+        // keeps going forward and down, bent toward the normal
+        assert!(d.x() > 0.0 && d.y() < 0.0);
+        assert!((d.x() * n - 0.5f64.sqrt()).abs() < 1e-12);
+        assert!(((d * d) - 1.0).abs() < 1e-12);
+
+        // and back out, parallel to the original direction
+        let outside = inside.refract(&V3::new(1.0, -1.0, 0.0), &normal, n);
+        let d = outside.direction();
+        assert!((d.x() - ray.direction().x()).abs() < 1e-12);
+        assert!((d.y() - ray.direction().y()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn glossy() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        let normal = V3::new(0.0, 1.0, 0.0);
+        let ray: Ray<f64> = Ray::new(
+            V3::new(-1.0, 1.0, 0.0),
+            V3::new(1.0, -1.0, 0.0).normalize(),
+            WaveLength(550.0),
+        );
+        let mirror: V3<f64> = V3::new(1.0, 1.0, 0.0).normalize();
+
+        let (ray_mirror, weight) = ray
+            .reflect(&V3::new(0.0, 0.0, 0.0), &normal, 0.0, 0.0, 0.0)
+            .unwrap();
+        assert!((ray_mirror.direction() * &mirror - 1.0).abs() < 1e-12);
+        assert_eq!(weight, 1.0);
+
+        // rougher surfaces spread the reflection wider around the mirror direction,
+        // the mean weight is the albedo, which the masking keeps below one
+        let spread = |roughness: f64, rng: &mut rand::rngs::ThreadRng| {
+            let n = 10000;
+            let (spread, albedo) = (0..n)
+                .filter_map(|_| {
+                    let a = rng.gen_range(0.0..std::f64::consts::TAU);
+                    let z = rng.gen_range(-1.0..1.0);
+                    ray.reflect(&V3::new(0.0, 0.0, 0.0), &normal, roughness, a, z)
+                })
+                .map(|(reflected, weight)| {
+                    assert!(reflected.direction().y() > 0.0);
+                    (1.0 - reflected.direction() * &mirror, weight)
+                })
+                .fold((0.0, 0.0), |(s, w), (ds, dw)| (s + ds, w + dw));
+            (spread / (n as f64), albedo / (n as f64))
+        };
+        let (smooth, smooth_albedo) = spread(0.05, &mut rng);
+        let (rough, rough_albedo) = spread(0.5, &mut rng);
+        assert!(smooth < 0.05);
+        assert!(rough > smooth * 4.0);
+        assert!(smooth_albedo > 0.95 && smooth_albedo < 1.02);
+        assert!(rough_albedo > 0.6 && rough_albedo < 1.02);
+    }
+
+    #[test]
+    fn absorption() {
+        // index one does not bend nor reflect, only the absorption remains
+        let slab = |thickness: f64| -> Vec<Primitive<CustomMaterial, f64>> {
+            vec![
+                Primitive::AxisAlignedBox(AxisAlignedBox::new(
+                    V3::new(-1.0, 1.0, -1.0 - thickness),
+                    V3::new(1.0, 3.0, -1.0),
+                    CustomMaterial::Dielectric {
+                        dispersion: Dispersion::Cauchy {
+                            a: 1.0,
+                            b: 0.0,
+                            c: 0.0,
+                        },
+                        absorption: Some(Spectrum::constant(0.5)),
+                        medium: None,
+                    },
+                )),
+                light(),
+            ]
+        };
+        let ray = Ray::new(
+            V3::new(0.0, 2.0, -3.0),
+            V3::new(0.0, 0.0, 1.0),
+            WaveLength(550.0),
+        );
+        let tracing = Tracing::default();
+        let light_only: Vec<Primitive<CustomMaterial, f64>> = vec![light()];
+        let (clear, _) = estimate(&light_only, &ray, &[], &tracing, 1);
+        assert!(clear > 0.0);
+        for &thickness in [0.5, 2.0].iter() {
+            let (value, error) = estimate(&slab(thickness), &ray, &[], &tracing, 20000);
+            let expected = clear * (-0.5 * thickness).exp();
+            assert!((value - expected).abs() < 5.0 * error.sqrt());
+        }
+    }
+
+    #[test]
+    fn fog() {
+        let scene: Vec<Primitive<CustomMaterial, f64>> = vec![
+            Primitive::Plane(Plane::new(
+                V3::new(0.0, 0.0, 0.0),
+                V3::new(0.0, 1.0, 0.0),
+                CustomMaterial::DiffuseWhite,
+            )),
+            light(),
+        ];
+        let lights = scene.lights();
+        let tracing = Tracing {
+            medium: Some(Medium {
+                absorption: Spectrum::constant(0.1),
+                scattering: Spectrum::constant(0.2),
+                asymmetry: 0.3,
+            }),
+            ..Tracing::default()
+        };
+
+        // looking at the light straight through the fog
+        let ray = Ray::new(
+            V3::new(0.0, 2.0, -3.0),
+            V3::new(0.0, 0.0, 1.0),
+            WaveLength(550.0),
+        );
+        let (clear, _) = estimate(&scene, &ray, &[], &Tracing::default(), 1);
+        let (foggy, _) = estimate(&scene, &ray, &[], &tracing, 50000);
+        // at least the unscattered part comes through, the in-scattering adds a little
+        assert!(foggy > clear * (-0.3f64 * 2.75).exp() * 0.95);
+        assert!(foggy < clear);
+
+        // the shadow rays are attenuated as much as the paths are
+        let ray = Ray::new(
+            V3::new(0.0, 1.0, -3.0),
+            V3::new(0.0, -1.0, 3.0).normalize(),
+            WaveLength(550.0),
+        );
+        let (path, path_error) = estimate(&scene, &ray, &[], &tracing, 50000);
+        let (direct, direct_error) = estimate(&scene, &ray, lights.as_ref(), &tracing, 50000);
+        assert!((path - direct).abs() < 5.0 * (path_error + direct_error).sqrt());
+    }
+
+    #[test]
+    fn immersed() {
+        // the clear slab in the absorbing smoke around the light
+        let smoke = Medium {
+            absorption: Spectrum::constant(0.2),
+            scattering: Spectrum::constant(0.0),
+            asymmetry: 0.0,
+        };
+        let scene: Vec<Primitive<CustomMaterial, f64>> = vec![
+            Primitive::AxisAlignedBox(AxisAlignedBox::new(
+                V3::new(-1.0, 1.0, -2.0),
+                V3::new(1.0, 3.0, 2.0),
+                CustomMaterial::Volume { medium: smoke },
+            )),
+            Primitive::AxisAlignedBox(AxisAlignedBox::new(
+                V3::new(-0.5, 1.5, -1.5),
+                V3::new(0.5, 2.5, -1.0),
+                CustomMaterial::Dielectric {
+                    dispersion: Dispersion::Constant(1.0),
+                    absorption: None,
+                    medium: None,
+                },
+            )),
+            light(),
+        ];
+        let ray = Ray::new(
+            V3::new(0.0, 2.0, -3.0),
+            V3::new(0.0, 0.0, 1.0),
+            WaveLength(550.0),
+        );
+        let tracing = Tracing::default();
+        let light_only: Vec<Primitive<CustomMaterial, f64>> = vec![light()];
+        let (clear, _) = estimate(&light_only, &ray, &[], &tracing, 1);
+
+        // the smoke is on the both sides of the slab, 0.5 before and 0.75 after it
+        let (value, error) = estimate(&scene, &ray, &[], &tracing, 20000);
+        let expected = clear * (-0.2f64 * 1.25).exp();
+        assert!((value - expected).abs() < 5.0 * error.sqrt());
+    }
+}
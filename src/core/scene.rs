@@ -1,9 +1,11 @@
 use super::ray::Ray;
 use super::algebra::V3;
 use super::wave::WaveLength;
+use super::medium::Medium;
 
 use num::Float;
 
+#[derive(Clone, Copy)]
 pub enum Side {
     Outer,
     Inner,
@@ -25,15 +27,46 @@ where
     Emission(f64),
     Decay,
     Diffuse,
+    /// Roughness of the microfacets, zero for the mirror.
     Reflect(C),
+    /// Index of the medium the ray leaves relative to the one it enters.
     Refract(C),
+    /// Diffuse re-emission of the photon absorbed at the given shorter wave length,
+    /// the path continues with it.
+    Fluorescence(WaveLength),
 }
 
 pub trait Material<C>
 where
     C: Float,
 {
-    fn fate(&self, wave_length: &WaveLength, side: Side, emission: f64, event: f64) -> Event<C>;
+    /// What happens to the photon hitting the surface, `cosine` is of the angle
+    /// between the ray and the normal, `emission` and `event` are uniform in `[0, 1)`.
+    fn fate(
+        &self,
+        wave_length: &WaveLength,
+        side: Side,
+        cosine: C,
+        emission: f64,
+        event: f64,
+    ) -> Event<C>;
+
+    /// Absorption coefficient of the interior per unit of the distance, the photon
+    /// leaving the interior is absorbed by the Beer-Lambert law.
+    fn absorption(&self, wave_length: &WaveLength) -> f64 {
+        let _ = wave_length;
+        0.0
+    }
+
+    /// Participating medium filling the interior, none by default.
+    fn medium(&self) -> Option<&Medium> {
+        None
+    }
+
+    /// Whether the surfaces of the material should be sampled directly as lights.
+    fn emissive(&self) -> bool {
+        false
+    }
 }
 
 pub struct Intersect<'a, M, C>
@@ -54,4 +87,72 @@ where
     type Material: Material<C>;
 
     fn find_intersect<'a>(&'a self, ray: &Ray<C>) -> Option<Intersect<'a, Self::Material, C>>;
+
+    /// Emissive spheres sampled by the next event estimation, none by default.
+    fn lights(&self) -> Vec<Light<C>> {
+        Vec::new()
+    }
+}
+
+/// Spherical light, directions toward it are sampled uniformly in the cone it subtends.
+#[derive(Clone)]
+pub struct Light<C>
+where
+    C: Float,
+{
+    center: V3<C>,
+    radius: C,
+}
+
+impl<C> Light<C>
+where
+    C: Float,
+{
+    pub fn new(center: V3<C>, radius: C) -> Self {
+        Light {
+            center: center,
+            radius: radius,
+        }
+    }
+
+    /// Cosine of the half angle of the cone, `None` if the position is inside the sphere.
+    fn cos_max(&self, position: &V3<C>) -> Option<C> {
+        let d = &self.center - position;
+        let sin_sq = self.radius * self.radius / (&d * &d);
+        if sin_sq < C::one() {
+            Some((C::one() - sin_sq).sqrt())
+        } else {
+            None
+        }
+    }
+
+    /// Solid angle density of the sampled direction from the position.
+    pub fn pdf(&self, position: &V3<C>) -> C {
+        use std::f64::consts::TAU;
+
+        self.cos_max(position)
+            .map(|cos_max| C::one() / (C::from(TAU).unwrap() * (C::one() - cos_max)))
+            .unwrap_or_else(C::zero)
+    }
+
+    /// Direction from the position toward the sphere, `u` and `v` are uniform in `[0, 1)`.
+    pub fn sample(&self, position: &V3<C>, u: C, v: C) -> Option<V3<C>> {
+        use std::f64::consts::TAU;
+
+        let cos_max = self.cos_max(position)?;
+        let w = (&self.center - position).normalize();
+        let (s, t) = w.frame();
+
+        let cos = C::one() - u * (C::one() - cos_max);
+        let sin = (C::one() - cos * cos).max(C::zero()).sqrt();
+        let phi = C::from(TAU).unwrap() * v;
+        Some(&(&(&s * (sin * phi.cos())) + &(&t * (sin * phi.sin()))) + &(&w * cos))
+    }
+
+    /// Whether the point lies on the sphere.
+    pub fn contains(&self, point: &V3<C>) -> bool {
+        let d = point - &self.center;
+        let tolerance = C::epsilon().sqrt() * C::from(16.0).unwrap();
+        ((&d * &d).sqrt() - self.radius).abs() <= tolerance * self.radius.max(C::one())
+    }
 }
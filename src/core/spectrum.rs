@@ -0,0 +1,306 @@
+use super::wave::WaveLength;
+use super::path::resolve_path;
+
+use std::{
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+use serde::{Serialize, Deserialize, Deserializer, de::Error};
+use serde_json::Value;
+
+/// Function of the wave length, linear between the points and constant beyond the ends.
+///
+/// In the scene file it is a number for the constant, the list of `[wave length, value]`
+/// pairs, or one of the tagged objects `{ "constant": 0.5 }`, `{ "points": [...] }` and
+/// `{ "csv": { "path": "file.csv", "column": 1 } }`, the latter reading the wave lengths
+/// from the first column and the values from the given one, the second by default.
+/// The path is relative to the scene file.
+#[derive(Clone, Serialize, Debug)]
+#[serde(into = "Vec<(f64, f64)>")]
+pub struct Spectrum {
+    points: Vec<(f64, f64)>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase", deny_unknown_fields)]
+pub enum SpectrumDescription {
+    Constant(f64),
+    Points(Vec<(f64, f64)>),
+    Csv {
+        path: PathBuf,
+        column: Option<usize>,
+    },
+}
+
+impl<'de> Deserialize<'de> for Spectrum {
+    // the number and the list are the shorthands for the constant and the points,
+    // told apart by the json type, so the error of the description reaches the user
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+        let description = match value {
+            Value::Number(_) => f64::deserialize(value).map(SpectrumDescription::Constant),
+            Value::Array(_) => {
+                Vec::<(f64, f64)>::deserialize(value).map(SpectrumDescription::Points)
+            },
+            value => SpectrumDescription::deserialize(value),
+        }
+        .map_err(|e| D::Error::custom(format!("spectrum: {}", e)))?;
+        Spectrum::try_from(description).map_err(D::Error::custom)
+    }
+}
+
+impl TryFrom<SpectrumDescription> for Spectrum {
+    type Error = SpectrumError;
+
+    fn try_from(description: SpectrumDescription) -> Result<Self, Self::Error> {
+        match description {
+            SpectrumDescription::Constant(value) => Ok(Spectrum::constant(value)),
+            SpectrumDescription::Points(points) => Spectrum::new(points),
+            SpectrumDescription::Csv {
+                path: path,
+                column: column,
+            } => Spectrum::load_csv(resolve_path(path), column.unwrap_or(1)),
+        }
+    }
+}
+
+impl From<Spectrum> for Vec<(f64, f64)> {
+    fn from(spectrum: Spectrum) -> Self {
+        spectrum.points
+    }
+}
+
+#[derive(Debug)]
+pub enum SpectrumError {
+    Io(io::Error),
+    Empty,
+    WrongNumber { line: usize },
+    MissingColumn { line: usize },
+}
+
+impl fmt::Display for SpectrumError {
+    #[allow(clippy::needless_borrowed_reference)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            &SpectrumError::Io(ref e) => write!(f, "cannot read spectrum file: {}", e),
+            &SpectrumError::Empty => write!(f, "spectrum has no points"),
+            &SpectrumError::WrongNumber { line: line } => {
+                write!(f, "wrong number at line {}", line)
+            },
+            &SpectrumError::MissingColumn { line: line } => {
+                write!(f, "missing column at line {}", line)
+            },
+        }
+    }
+}
+
+impl From<io::Error> for SpectrumError {
+    fn from(e: io::Error) -> Self {
+        SpectrumError::Io(e)
+    }
+}
+
+impl Spectrum {
+    pub fn constant(value: f64) -> Self {
+        Spectrum {
+            points: vec![(0.0, value)],
+        }
+    }
+
+    /// The points are `(wave length in nanometers, value)` in any order.
+    pub fn new(points: Vec<(f64, f64)>) -> Result<Self, SpectrumError> {
+        let mut points = points;
+        if points.is_empty() {
+            return Err(SpectrumError::Empty);
+        }
+        points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        Ok(Spectrum { points: points })
+    }
+
+    pub fn load_csv<P>(path: P, column: usize) -> Result<Self, SpectrumError>
+    where
+        P: AsRef<Path>,
+    {
+        let text = fs::read_to_string(path)?;
+        Self::parse_csv(text.as_str(), column)
+    }
+
+    pub fn parse_csv(text: &str, column: usize) -> Result<Self, SpectrumError> {
+        let rows = parse_csv(text)?;
+        let points = rows
+            .into_iter()
+            .map(|(line, row)| match (row.first(), row.get(column)) {
+                (Some(&l), Some(&value)) => Ok((l, value)),
+                _ => Err(SpectrumError::MissingColumn { line: line }),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::new(points)
+    }
+
+    pub fn value(&self, wave_length: &WaveLength) -> f64 {
+        let l = wave_length.0;
+        let points = &self.points;
+        let i = points.partition_point(|&(x, _)| x < l);
+        if i == 0 {
+            points[0].1
+        } else if i == points.len() {
+            points[i - 1].1
+        } else {
+            let (x0, y0) = points[i - 1];
+            let (x1, y1) = points[i];
+            y0 + (y1 - y0) * (l - x0) / (x1 - x0)
+        }
+    }
+
+    /// Wave length distributed proportionally to the spectrum between its first and last
+    /// point, `u` is uniform in `[0, 1)`, `None` if there is nothing to sample.
+    pub fn sample(&self, u: f64) -> Option<WaveLength> {
+        let area = |&(x0, y0): &(f64, f64), &(x1, y1): &(f64, f64)| (x1 - x0) * (y0 + y1) / 2.0;
+        let total = self
+            .points
+            .windows(2)
+            .map(|w| area(&w[0], &w[1]))
+            .sum::<f64>();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut rest = u * total;
+        for w in self.points.windows(2) {
+            let ((x0, y0), (x1, y1)) = (w[0], w[1]);
+            let a = area(&w[0], &w[1]);
+            if rest >= a {
+                rest -= a;
+                continue;
+            }
+            // invert the integral of the linear function, `(x1 - x0) (y0 t + (y1 - y0) t² / 2)`
+            let width = x1 - x0;
+            let slope = (y1 - y0) / 2.0;
+            let t = if slope.abs() < 1e-12 {
+                rest / (width * y0)
+            } else {
+                (-y0 + (y0 * y0 + 4.0 * slope * rest / width).max(0.0).sqrt()) / (2.0 * slope)
+            };
+            return Some(WaveLength(x0 + width * t.clamp(0.0, 1.0)));
+        }
+        self.points.last().map(|&(x, _)| WaveLength(x))
+    }
+
+    /// Like `sample`, but only of the part of the spectrum below the `limit`.
+    pub fn sample_below(&self, limit: &WaveLength, u: f64) -> Option<WaveLength> {
+        let l = limit.0;
+        let mut points = self
+            .points
+            .iter()
+            .cloned()
+            .take_while(|&(x, _)| x < l)
+            .collect::<Vec<_>>();
+        if points.len() < self.points.len() {
+            points.push((l, self.value(limit)));
+        }
+        Spectrum { points: points }.sample(u)
+    }
+}
+
+/// Rows of numbers with their line numbers, skips empty lines, `#` comments
+/// and the header in the first line.
+pub(super) fn parse_csv(text: &str) -> Result<Vec<(usize, Vec<f64>)>, SpectrumError> {
+    let mut rows = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let row = line
+            .split(',')
+            .map(|field| field.trim().parse::<f64>())
+            .collect::<Result<Vec<_>, _>>();
+        match row {
+            Ok(row) => rows.push((line_number, row)),
+            Err(_) if index == 0 => (),
+            Err(_) => return Err(SpectrumError::WrongNumber { line: line_number }),
+        }
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Spectrum, SpectrumError};
+    use crate::core::{WaveLength, with_base_directory};
+
+    #[test]
+    fn interpolate() {
+        let spectrum: Spectrum =
+            serde_json::from_str("[[600, 1.0], [400, 0.0], [500, 0.2]]").unwrap();
+        let value = |l| spectrum.value(&WaveLength(l));
+        assert_eq!(value(300.0), 0.0);
+        assert!((value(450.0) - 0.1).abs() < 1e-12);
+        assert!((value(550.0) - 0.6).abs() < 1e-12);
+        assert_eq!(value(800.0), 1.0);
+
+        let constant: Spectrum = serde_json::from_str("0.5").unwrap();
+        assert_eq!(constant.value(&WaveLength(550.0)), 0.5);
+        assert!(serde_json::from_str::<Spectrum>("[]").is_err());
+
+        let tagged: Spectrum = serde_json::from_str(r#"{ "constant": 0.5 }"#).unwrap();
+        assert_eq!(tagged.value(&WaveLength(550.0)), 0.5);
+        let tagged: Spectrum = serde_json::from_str(r#"{ "points": [[500, 0.2]] }"#).unwrap();
+        assert_eq!(tagged.value(&WaveLength(550.0)), 0.2);
+        let message = serde_json::from_str::<Spectrum>(r#"{ "csv": { "pth": "a.csv" } }"#)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(message.contains("path"), "{}", message);
+    }
+
+    #[test]
+    fn sample() {
+        // triangle peaking at 500, the half of the area is below it
+        let spectrum = Spectrum::new(vec![(400.0, 0.0), (500.0, 2.0), (600.0, 0.0)]).unwrap();
+        let sample = |u| spectrum.sample(u).unwrap().0;
+        assert!((sample(0.0) - 400.0).abs() < 1e-9);
+        assert!((sample(0.5) - 500.0).abs() < 1e-9);
+        assert!((sample(0.125) - 450.0).abs() < 1e-9);
+        assert!((sample(0.875) - 550.0).abs() < 1e-9);
+        assert!(Spectrum::constant(1.0).sample(0.5).is_none());
+
+        // the rising half of the triangle
+        let below = |u| spectrum.sample_below(&WaveLength(500.0), u).unwrap().0;
+        assert!((below(0.25) - 450.0).abs() < 1e-9);
+        assert!((below(0.999) - 500.0).abs() < 0.1);
+        assert!((spectrum.sample_below(&WaveLength(700.0), 0.5).unwrap().0 - 500.0).abs() < 1e-9);
+        assert!(spectrum.sample_below(&WaveLength(400.0), 0.5).is_none());
+    }
+
+    #[test]
+    fn csv() {
+        let text = "wave length,red,blue\n# measured\n400, 0.1, 0.9\n\n700, 0.8, 0.05\n";
+        let blue = Spectrum::parse_csv(text, 2).unwrap();
+        assert!((blue.value(&WaveLength(400.0)) - 0.9).abs() < 1e-12);
+        match Spectrum::parse_csv(text, 3) {
+            Err(SpectrumError::MissingColumn { line: 3 }) => (),
+            _ => panic!("expected missing column error"),
+        }
+        match Spectrum::parse_csv("400, 0.1\n500, x\n", 1) {
+            Err(SpectrumError::WrongNumber { line: 2 }) => (),
+            _ => panic!("expected wrong number error"),
+        }
+    }
+
+    #[test]
+    fn csv_relative_to_scene() {
+        let json = r#"{ "csv": { "path": "ciexyz64_1.csv", "column": 2 } }"#;
+        let y: Spectrum =
+            with_base_directory(env!("CARGO_MANIFEST_DIR"), || serde_json::from_str(json)).unwrap();
+        assert!((y.value(&WaveLength(555.0)) - 1.0).abs() < 0.01);
+        let missing = with_base_directory("missing", || serde_json::from_str::<Spectrum>(json));
+        assert!(missing.is_err());
+    }
+}
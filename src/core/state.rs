@@ -0,0 +1,390 @@
+use super::buffer::Buffer;
+use super::image::crc32;
+use super::wave::WaveLengthFactory;
+
+use std::{
+    fmt,
+    convert::TryFrom,
+    io::{self, Read, Write},
+};
+
+const MAGIC: &[u8; 8] = b"GUSNISTA";
+// the fixed wave lengths weight the share of the range since the version 2,
+// the older states are brighter and cannot be merged with the newer ones
+const VERSION: u32 = 2;
+
+#[derive(Debug)]
+pub enum StateError {
+    Io(io::Error),
+    Magic,
+    Version(u32),
+    Truncated,
+    Checksum,
+    /// The sizes in the header do not agree with the content.
+    Corrupt,
+    Factory {
+        expected: String,
+        found: String,
+    },
+    Resolution {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+    Scene,
+}
+
+impl fmt::Display for StateError {
+    #[allow(clippy::needless_borrowed_reference)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            &StateError::Io(ref e) => write!(f, "cannot access state file: {}", e),
+            &StateError::Magic => write!(f, "not a state file"),
+            &StateError::Version(v) => write!(f, "state file version {} is unsupported", v),
+            &StateError::Truncated => write!(f, "state file is truncated"),
+            &StateError::Checksum => write!(f, "state file is corrupted, checksum mismatch"),
+            &StateError::Corrupt => write!(f, "state file is corrupted, size mismatch"),
+            &StateError::Factory {
+                expected: ref expected,
+                found: ref found,
+            } => write!(
+                f,
+                "state file is traced with wave lengths \'{}\', expected \'{}\'",
+                found, expected
+            ),
+            &StateError::Resolution {
+                expected: (ew, eh),
+                found: (fw, fh),
+            } => write!(
+                f,
+                "state file resolution is {}x{}, expected {}x{}",
+                fw, fh, ew, eh
+            ),
+            &StateError::Scene => write!(f, "state file is traced with another scene or eye"),
+        }
+    }
+}
+
+impl From<io::Error> for StateError {
+    fn from(e: io::Error) -> Self {
+        StateError::Io(e)
+    }
+}
+
+/// Hash of the scene and the eye descriptions, FNV-1a.
+pub fn scene_hash(scene: &[u8], eye: &[u8]) -> u64 {
+    let mut hash = 0xcbf29ce484222325u64;
+    for &byte in scene.iter().chain(&[0]).chain(eye.iter()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// Progress of the render which can be resumed or merged with others.
+///
+/// The file is little endian: magic `GUSNISTA`, version `u32`, width, height and sample count
+/// as `u64`, the wave length factory identity as `u32` length and utf-8 bytes,
+/// the scene hash `u64`, the accumulated XYZ and the squares as `f64`,
+/// and the CRC-32 `u32` of everything before.
+pub struct State<F>
+where
+    F: WaveLengthFactory,
+{
+    pub buffer: Buffer<F>,
+    pub scene_hash: u64,
+}
+
+impl<F> State<F>
+where
+    F: WaveLengthFactory,
+{
+    pub fn new(buffer: Buffer<F>, scene_hash: u64) -> Self {
+        State {
+            buffer: buffer,
+            scene_hash: scene_hash,
+        }
+    }
+
+    pub fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: Write,
+    {
+        let buffer = &self.buffer;
+        let identity = buffer.factory().identity();
+
+        let size = 48 + identity.len() + 8 * (buffer.data().len() + buffer.squares().len());
+        let mut bytes = Vec::with_capacity(size);
+        bytes.extend_from_slice(MAGIC.as_ref());
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(buffer.width() as u64).to_le_bytes());
+        bytes.extend_from_slice(&(buffer.height() as u64).to_le_bytes());
+        bytes.extend_from_slice(&(buffer.sample_count() as u64).to_le_bytes());
+        bytes.extend_from_slice(&(identity.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(identity.as_bytes());
+        bytes.extend_from_slice(&self.scene_hash.to_le_bytes());
+        for value in buffer.data().iter().chain(buffer.squares().iter()) {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        let checksum = crc32(bytes.as_ref());
+        bytes.extend_from_slice(&checksum.to_le_bytes());
+
+        writer.write_all(bytes.as_ref())
+    }
+
+    /// Reads the state traced with the same kind of the wave length factory.
+    pub fn read<R>(reader: &mut R, factory: F) -> Result<Self, StateError>
+    where
+        R: Read,
+    {
+        Self::read_with(reader, |identity| {
+            if identity == factory.identity() {
+                Ok(factory)
+            } else {
+                Err(StateError::Factory {
+                    expected: factory.identity(),
+                    found: identity.to_owned(),
+                })
+            }
+        })
+    }
+
+    /// Reads the state with the factory made for the identity found in the file.
+    pub fn read_with<R, G>(reader: &mut R, factory: G) -> Result<Self, StateError>
+    where
+        R: Read,
+        G: FnOnce(&str) -> Result<F, StateError>,
+    {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        if bytes.len() < MAGIC.len() || &bytes[..MAGIC.len()] != MAGIC.as_ref() {
+            return Err(StateError::Magic);
+        }
+        // the version and the checksum
+        if bytes.len() < MAGIC.len() + 4 + 4 {
+            return Err(StateError::Truncated);
+        }
+        let (content, checksum) = bytes.split_at(bytes.len() - 4);
+
+        let mut cursor = Cursor {
+            bytes: content,
+            position: MAGIC.len(),
+        };
+        let version = u32::from_le_bytes(cursor.array()?);
+        if version != VERSION {
+            return Err(StateError::Version(version));
+        }
+        if crc32(content) != u32::from_le_bytes(TryFrom::try_from(checksum).unwrap()) {
+            return Err(StateError::Checksum);
+        }
+
+        let width = u64::from_le_bytes(cursor.array()?) as usize;
+        let height = u64::from_le_bytes(cursor.array()?) as usize;
+        let sample_count = u64::from_le_bytes(cursor.array()?) as usize;
+        let identity_length = u32::from_le_bytes(cursor.array()?) as usize;
+        let identity = String::from_utf8_lossy(cursor.take(identity_length)?).into_owned();
+        let factory = factory(identity.as_str())?;
+        let scene_hash = u64::from_le_bytes(cursor.array()?);
+
+        // the sizes are checked before anything is allocated for them
+        let mut floats = |count: usize| -> Result<Vec<f64>, StateError> {
+            let length = count.checked_mul(8).ok_or(StateError::Corrupt)?;
+            Ok(cursor
+                .take(length)?
+                .chunks(8)
+                .map(|chunk| f64::from_le_bytes(TryFrom::try_from(chunk).unwrap()))
+                .collect())
+        };
+        let pixels = width.checked_mul(height).ok_or(StateError::Corrupt)?;
+        let data = floats(pixels.checked_mul(3).ok_or(StateError::Corrupt)?)?;
+        let squares = floats(pixels)?;
+        if cursor.position != content.len() {
+            return Err(StateError::Corrupt);
+        }
+
+        Ok(State {
+            buffer: Buffer::from_parts(width, height, data, squares, sample_count, factory),
+            scene_hash: scene_hash,
+        })
+    }
+
+    /// Checks the state belongs to the render of the scene at the resolution.
+    pub fn validate(&self, width: usize, height: usize, scene_hash: u64) -> Result<(), StateError> {
+        let found = (self.buffer.width(), self.buffer.height());
+        if found != (width, height) {
+            Err(StateError::Resolution {
+                expected: (width, height),
+                found: found,
+            })
+        } else if self.scene_hash != scene_hash {
+            Err(StateError::Scene)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Sums the samples of the independent render of the same scene into this state.
+    pub fn merge(&mut self, other: State<F>) -> Result<(), StateError> {
+        let expected = self.buffer.factory().identity();
+        let found = other.buffer.factory().identity();
+        if expected != found {
+            return Err(StateError::Factory {
+                expected: expected,
+                found: found,
+            });
+        }
+        other.validate(self.buffer.width(), self.buffer.height(), self.scene_hash)?;
+
+        let mut other = other;
+        self.buffer += &mut other.buffer;
+        Ok(())
+    }
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], StateError> {
+        match self.position.checked_add(length) {
+            Some(end) if end <= self.bytes.len() => {
+                let slice = &self.bytes[self.position..end];
+                self.position = end;
+                Ok(slice)
+            },
+            _ => Err(StateError::Truncated),
+        }
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], StateError> {
+        self.take(N).map(|slice| TryFrom::try_from(slice).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{State, StateError, scene_hash};
+    use crate::core::{Buffer, WaveLengthTrimmedFactory, WaveLengthLinearFactory};
+
+    fn state() -> State<WaveLengthTrimmedFactory> {
+        let data = (0..24).map(|i| i as f64 * 0.5).collect();
+        let squares = (0..8).map(|i| i as f64).collect();
+        let buffer = Buffer::from_parts(4, 2, data, squares, 3, WaveLengthTrimmedFactory);
+        State::new(buffer, scene_hash(b"scene", b"eye"))
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut bytes = Vec::new();
+        state().write(&mut bytes).unwrap();
+
+        let loaded = State::read(&mut bytes.as_slice(), WaveLengthTrimmedFactory).unwrap();
+        assert_eq!(loaded.buffer.sample_count(), 3);
+        assert_eq!(loaded.buffer.data(), state().buffer.data());
+        assert_eq!(loaded.buffer.squares(), state().buffer.squares());
+        loaded.validate(4, 2, scene_hash(b"scene", b"eye")).unwrap();
+
+        match loaded.validate(2, 4, scene_hash(b"scene", b"eye")) {
+            Err(StateError::Resolution { .. }) => (),
+            _ => panic!("expected resolution error"),
+        }
+        match loaded.validate(4, 2, scene_hash(b"scene", b"other eye")) {
+            Err(StateError::Scene) => (),
+            _ => panic!("expected scene error"),
+        }
+    }
+
+    #[test]
+    fn merge() {
+        let mut merged = state();
+        merged.merge(state()).unwrap();
+        assert_eq!(merged.buffer.sample_count(), 6);
+        assert_eq!(merged.buffer.data()[3], 3.0);
+        assert_eq!(merged.buffer.squares()[7], 14.0);
+
+        let other = State::new(state().buffer, scene_hash(b"other scene", b"eye"));
+        match merged.merge(other) {
+            Err(StateError::Scene) => (),
+            _ => panic!("expected scene error"),
+        }
+        assert_eq!(merged.buffer.sample_count(), 6);
+    }
+
+    #[test]
+    fn corrupted() {
+        let mut bytes = Vec::new();
+        state().write(&mut bytes).unwrap();
+
+        match State::read(&mut bytes.as_slice(), WaveLengthLinearFactory::new(10)) {
+            Err(StateError::Factory { .. }) => (),
+            _ => panic!("expected factory error"),
+        }
+
+        let mut broken = bytes.clone();
+        broken[100] ^= 1;
+        match State::read(&mut broken.as_slice(), WaveLengthTrimmedFactory) {
+            Err(StateError::Checksum) => (),
+            _ => panic!("expected checksum error"),
+        }
+
+        match State::read(&mut &bytes[..20], WaveLengthTrimmedFactory) {
+            Err(StateError::Checksum) => (),
+            _ => panic!("expected checksum error"),
+        }
+
+        match State::read(&mut &b"width and height"[..], WaveLengthTrimmedFactory) {
+            Err(StateError::Magic) => (),
+            _ => panic!("expected magic error"),
+        }
+    }
+
+    #[test]
+    fn sizes() {
+        use crate::core::image::crc32;
+
+        // rewrites the part of the content keeping the checksum right
+        let forge = |bytes: &[u8], position: usize, patch: &[u8], extra: &[u8]| {
+            let mut content = bytes[..(bytes.len() - 4)].to_vec();
+            content[position..(position + patch.len())].copy_from_slice(patch);
+            content.extend_from_slice(extra);
+            let checksum = crc32(content.as_ref());
+            content.extend_from_slice(&checksum.to_le_bytes());
+            content
+        };
+        let mut bytes = Vec::new();
+        state().write(&mut bytes).unwrap();
+
+        match State::read(&mut &bytes[..14], WaveLengthTrimmedFactory) {
+            Err(StateError::Truncated) => (),
+            _ => panic!("expected truncated error"),
+        }
+
+        // width and height multiply beyond the address space
+        let huge = forge(&bytes, 12, &(1u64 << 40).to_le_bytes(), &[]);
+        let huge = forge(&huge, 20, &(1u64 << 40).to_le_bytes(), &[]);
+        match State::read(&mut huge.as_slice(), WaveLengthTrimmedFactory) {
+            Err(StateError::Corrupt) => (),
+            _ => panic!("expected corrupt error"),
+        }
+
+        let large = forge(&bytes, 12, &(1u64 << 20).to_le_bytes(), &[]);
+        match State::read(&mut large.as_slice(), WaveLengthTrimmedFactory) {
+            Err(StateError::Truncated) => (),
+            _ => panic!("expected truncated error"),
+        }
+
+        let older = forge(&bytes, 8, &1u32.to_le_bytes(), &[]);
+        match State::read(&mut older.as_slice(), WaveLengthTrimmedFactory) {
+            Err(StateError::Version(1)) => (),
+            _ => panic!("expected version error"),
+        }
+
+        let trailing = forge(&bytes, 0, &[], &[0; 8]);
+        match State::read(&mut trailing.as_slice(), WaveLengthTrimmedFactory) {
+            Err(StateError::Corrupt) => (),
+            _ => panic!("expected corrupt error"),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use serde::{Serialize, Deserialize};
+
+/// How the mean radiance of the buffer is scaled before the tone mapping.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum Exposure {
+    Scale(f64),
+    /// Scales the log-average luminance of the image to the `key`, 0.18 is the middle gray.
+    Auto {
+        key: f64,
+    },
+}
+
+impl Default for Exposure {
+    fn default() -> Self {
+        Exposure::Auto { key: 0.18 }
+    }
+}
+
+/// Compresses the linear value into the displayable range, applied to each channel.
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub enum ToneMapping {
+    /// Clips at one.
+    #[default]
+    Linear,
+    Reinhard,
+    /// Reinhard which maps the `white` to one.
+    ExtendedReinhard {
+        white: f64,
+    },
+    /// Narkowicz fit of the ACES filmic curve.
+    Aces,
+    /// Hable filmic curve from Uncharted 2.
+    Hable,
+}
+
+impl ToneMapping {
+    pub fn map(&self, value: f64) -> f64 {
+        let x = value.max(0.0);
+        match self {
+            &ToneMapping::Linear => x.min(1.0),
+            &ToneMapping::Reinhard => x / (1.0 + x),
+            &ToneMapping::ExtendedReinhard { white: w } => {
+                (x * (1.0 + x / (w * w)) / (1.0 + x)).min(1.0)
+            },
+            &ToneMapping::Aces => {
+                let (a, b, c, d, e) = (2.51, 0.03, 2.43, 0.59, 0.14);
+                (x * (a * x + b) / (x * (c * x + d) + e)).min(1.0)
+            },
+            &ToneMapping::Hable => {
+                let f = |x: f64| {
+                    let (a, b, c, d, e, f) = (0.15, 0.50, 0.10, 0.20, 0.02, 0.30);
+                    (x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f) - e / f
+                };
+                let white = 11.2;
+                let exposure_bias = 2.0;
+                (f(x * exposure_bias) / f(white)).min(1.0)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ToneMapping;
+
+    #[test]
+    fn range() {
+        let operators = [
+            ToneMapping::Linear,
+            ToneMapping::Reinhard,
+            ToneMapping::ExtendedReinhard { white: 4.0 },
+            ToneMapping::Aces,
+            ToneMapping::Hable,
+        ];
+        for operator in operators.iter() {
+            assert!(operator.map(0.0).abs() < 1e-9);
+            assert!(operator.map(-1.0).abs() < 1e-9);
+            let mut last = 0.0;
+            for i in 1..1000 {
+                let y = operator.map(i as f64 * 0.02);
+                assert!(y >= last && y <= 1.0);
+                last = y;
+            }
+        }
+        assert!((ToneMapping::ExtendedReinhard { white: 4.0 }.map(4.0) - 1.0).abs() < 1e-12);
+        assert!((ToneMapping::Reinhard.map(1.0) - 0.5).abs() < 1e-12);
+    }
+}
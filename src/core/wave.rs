@@ -1,68 +1,19 @@
-use std::{
-    ops::{Add, Mul},
-    iter::Iterator,
-};
-use serde::{Serialize, Deserialize};
-
-#[derive(Default, Clone, Serialize, Deserialize, Debug)]
-pub struct Rgb {
-    r: f64,
-    g: f64,
-    b: f64,
-}
-
-impl Rgb {
-    pub fn new(r: f64, g: f64, b: f64) -> Self {
-        Rgb { r: r, g: g, b: b }
-    }
-
-    pub fn tuple(&self, reverse: bool) -> (f64, f64, f64) {
-        if reverse {
-            (self.b, self.g, self.r)
-        } else {
-            (self.r, self.g, self.b)
-        }
-    }
-
-    pub fn write(&self, reverse: bool, buffer: &mut [u8]) {
-        let to_byte = |a: f64| -> u8 {
-            if a >= 1.0 {
-                255
-            } else if a <= 0.0 {
-                0
-            } else {
-                (a * 255.0) as u8
-            }
-        };
-        let (a, b, c) = self.tuple(reverse);
-        buffer[0] = to_byte(a);
-        buffer[1] = to_byte(b);
-        buffer[2] = to_byte(c);
-    }
-}
-
-impl Mul<f64> for Rgb {
-    type Output = Rgb;
-
-    fn mul(self, rhs: f64) -> Self::Output {
-        Rgb::new(self.r * rhs, self.g * rhs, self.b * rhs)
-    }
-}
+use super::color::Xyz;
+use super::matching::ColorMatching;
 
-impl Add for Rgb {
-    type Output = Rgb;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Rgb::new(self.r + rhs.r, self.g + rhs.g, self.b + rhs.b)
-    }
-}
+use std::{iter::Iterator, vec};
+use serde::{Serialize, Deserialize};
+use rand::Rng;
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct WaveLength(pub f64);
 
 impl WaveLength {
+    /// Value of the color matching functions, the color of the unit of energy
+    /// at this wave length.
     #[inline(always)]
-    pub fn color(&self) -> Rgb {
+    #[allow(clippy::needless_borrowed_reference)]
+    pub fn xyz(&self) -> Xyz {
         use num::Float;
 
         match self {
@@ -70,7 +21,7 @@ impl WaveLength {
                 let first = TABLE.first().unwrap().0;
                 let last = TABLE.last().unwrap().0;
                 if *l < first || *l > last {
-                    Rgb::new(0.0, 0.0, 0.0)
+                    Xyz::default()
                 } else {
                     let i = (*l - first) as usize;
                     let l = l.fract();
@@ -86,10 +37,20 @@ impl WaveLength {
 }
 
 pub trait WaveLengthFactory {
-    type Iter: Iterator<Item = WaveLength>;
+    type Iter: Iterator<Item = (WaveLength, f64)>;
 
-    fn iter(&self) -> Self::Iter;
+    /// The wave lengths of one sample of the pixel with their weights, the density
+    /// of the uniform wave length on the range of the color matching table over
+    /// the density of the sampled one. The fixed wave lengths weight the share
+    /// of the range each of them stands for, so every factory estimates the same mean.
+    fn iter<R>(&self, rng: &mut R) -> Self::Iter
+    where
+        R: Rng;
+    /// How many wave lengths are in one sample of the pixel.
     fn resolution(&self) -> usize;
+    /// Distinguishes the sampling in the state file, buffers traced with
+    /// different factories cannot be combined.
+    fn identity(&self) -> String;
 }
 
 #[derive(Clone)]
@@ -114,7 +75,11 @@ impl WaveLengthLinearFactory {
 impl WaveLengthFactory for WaveLengthLinearFactory {
     type Iter = WaveLengthLinear;
 
-    fn iter(&self) -> Self::Iter {
+    fn iter<R>(&self, rng: &mut R) -> Self::Iter
+    where
+        R: Rng,
+    {
+        let _ = rng;
         WaveLengthLinear {
             resolution: self.resolution,
             position: 0,
@@ -124,10 +89,14 @@ impl WaveLengthFactory for WaveLengthLinearFactory {
     fn resolution(&self) -> usize {
         self.resolution
     }
+
+    fn identity(&self) -> String {
+        format!("linear {}", self.resolution)
+    }
 }
 
 impl Iterator for WaveLengthLinear {
-    type Item = WaveLength;
+    type Item = (WaveLength, f64);
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.position == self.resolution {
@@ -136,8 +105,14 @@ impl Iterator for WaveLengthLinear {
             let first = TABLE.first().unwrap().0;
             let last = TABLE.last().unwrap().0;
             let offset = (last - first) * (self.position as f64) / ((self.resolution - 1) as f64);
+            // the trapezoids between the wave lengths, the ends stand for the half
+            let share = (self.resolution as f64) / ((self.resolution - 1) as f64);
+            let end = self.position == 0 || self.position + 1 == self.resolution;
             self.position += 1;
-            Some(WaveLength(first + offset))
+            Some((
+                WaveLength(first + offset),
+                if end { share / 2.0 } else { share },
+            ))
         }
     }
 }
@@ -145,512 +120,926 @@ impl Iterator for WaveLengthLinear {
 #[derive(Clone)]
 pub struct WaveLengthTrimmedFactory;
 
+#[derive(Default)]
 pub struct WaveLengthTrimmed {
     position: usize,
 }
 
-impl Default for WaveLengthTrimmed {
-    fn default() -> Self {
-        WaveLengthTrimmed { position: 0 }
-    }
-}
-
 impl WaveLengthFactory for WaveLengthTrimmedFactory {
     type Iter = WaveLengthTrimmed;
 
-    fn iter(&self) -> Self::Iter {
+    fn iter<R>(&self, rng: &mut R) -> Self::Iter
+    where
+        R: Rng,
+    {
+        let _ = rng;
         WaveLengthTrimmed { position: 0 }
     }
 
     fn resolution(&self) -> usize {
         360
     }
+
+    fn identity(&self) -> String {
+        "trimmed 360".to_owned()
+    }
 }
 
 impl Iterator for WaveLengthTrimmed {
-    type Item = WaveLength;
+    type Item = (WaveLength, f64);
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.position == 360 {
             None
         } else {
+            // a nanometer apart, each stands for a nanometer of the whole range
+            let (first, last) = range();
             self.position += 1;
-            Some(WaveLength(380.0 + (self.position as f64)))
+            Some((
+                WaveLength(380.0 + (self.position as f64)),
+                360.0 / (last - first),
+            ))
+        }
+    }
+}
+
+/// Range of the color matching table, where the random wave lengths are.
+fn range() -> (f64, f64) {
+    (TABLE.first().unwrap().0, TABLE.last().unwrap().0)
+}
+
+/// Uniformly random wave lengths, independent of each other.
+#[derive(Clone)]
+pub struct WaveLengthUniformFactory {
+    count: usize,
+}
+
+impl WaveLengthUniformFactory {
+    pub fn new(count: usize) -> Self {
+        assert!(count > 0);
+        WaveLengthUniformFactory { count: count }
+    }
+}
+
+impl WaveLengthFactory for WaveLengthUniformFactory {
+    type Iter = vec::IntoIter<(WaveLength, f64)>;
+
+    fn iter<R>(&self, rng: &mut R) -> Self::Iter
+    where
+        R: Rng,
+    {
+        let (first, last) = range();
+        (0..self.count)
+            .map(|_| (WaveLength(rng.gen_range(first..last)), 1.0))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn resolution(&self) -> usize {
+        self.count
+    }
+
+    fn identity(&self) -> String {
+        format!("uniform {}", self.count)
+    }
+}
+
+/// The range split into the equal strata, one random wave length in each.
+#[derive(Clone)]
+pub struct WaveLengthStratifiedFactory {
+    count: usize,
+}
+
+impl WaveLengthStratifiedFactory {
+    pub fn new(count: usize) -> Self {
+        assert!(count > 0);
+        WaveLengthStratifiedFactory { count: count }
+    }
+}
+
+impl WaveLengthFactory for WaveLengthStratifiedFactory {
+    type Iter = vec::IntoIter<(WaveLength, f64)>;
+
+    fn iter<R>(&self, rng: &mut R) -> Self::Iter
+    where
+        R: Rng,
+    {
+        let (first, last) = range();
+        let count = self.count as f64;
+        (0..self.count)
+            .map(|i| {
+                let u = ((i as f64) + rng.gen_range(0.0..1.0)) / count;
+                (WaveLength(first + (last - first) * u), 1.0)
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn resolution(&self) -> usize {
+        self.count
+    }
+
+    fn identity(&self) -> String {
+        format!("stratified {}", self.count)
+    }
+}
+
+/// Wave lengths distributed proportionally to the sum of the responses of the observer,
+/// stratified. The luminance alone would rarely sample the blue end where `z` dominates,
+/// and the rare samples would weight too much.
+#[derive(Clone)]
+pub struct WaveLengthImportanceFactory {
+    count: usize,
+    /// Cumulative density at the end of each interval between the rows of the table.
+    cdf: Vec<f64>,
+    /// Identity of the observer, the density depends on it.
+    observer: String,
+}
+
+impl WaveLengthImportanceFactory {
+    /// The density is taken at the rows of the color matching table, the observer
+    /// responding nowhere there is sampled uniformly.
+    pub fn new(count: usize, observer: &ColorMatching) -> Self {
+        assert!(count > 0);
+        let mut density = TABLE
+            .iter()
+            .map(|&(l, _)| {
+                let (x, y, z) = observer.xyz(&WaveLength(l)).tuple();
+                (x + y + z).max(0.0)
+            })
+            .collect::<Vec<_>>();
+        if density.iter().all(|&d| d == 0.0) {
+            density = vec![1.0; TABLE.len()];
+        }
+        let cdf = (1..TABLE.len())
+            .scan(0.0, |sum, i| {
+                let width = TABLE[i].0 - TABLE[i - 1].0;
+                *sum += width * (density[i - 1] + density[i]) / 2.0;
+                Some(*sum)
+            })
+            .collect();
+        WaveLengthImportanceFactory {
+            count: count,
+            cdf: cdf,
+            observer: observer.identity(),
+        }
+    }
+
+    /// The wave length at the cumulative density and its density relative to the uniform one.
+    fn invert(&self, u: f64) -> (WaveLength, f64) {
+        let (first, last) = range();
+        let total = *self.cdf.last().unwrap();
+        let target = u * total;
+        let i = self
+            .cdf
+            .partition_point(|&c| c <= target)
+            .min(self.cdf.len() - 1);
+        let start = if i == 0 { 0.0 } else { self.cdf[i - 1] };
+        let (x0, x1) = (TABLE[i].0, TABLE[i + 1].0);
+        let mass = self.cdf[i] - start;
+        let l = x0 + (x1 - x0) * (target - start) / mass;
+        let pdf = mass / ((x1 - x0) * total);
+        (WaveLength(l), pdf * (last - first))
+    }
+}
+
+impl WaveLengthFactory for WaveLengthImportanceFactory {
+    type Iter = vec::IntoIter<(WaveLength, f64)>;
+
+    fn iter<R>(&self, rng: &mut R) -> Self::Iter
+    where
+        R: Rng,
+    {
+        let count = self.count as f64;
+        (0..self.count)
+            .map(|i| {
+                let u = ((i as f64) + rng.gen_range(0.0..1.0)) / count;
+                let (l, density) = self.invert(u);
+                (l, 1.0 / density)
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn resolution(&self) -> usize {
+        self.count
+    }
+
+    fn identity(&self) -> String {
+        format!("importance {} {}", self.count, self.observer)
+    }
+}
+
+/// How the wave lengths of the render are chosen, in the scene file `"Trimmed"`,
+/// or the kind with the count, like `{ "Importance": 16 }`.
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub enum WaveLengthSampling {
+    /// The fixed wave lengths a nanometer apart from 381 to 740.
+    #[default]
+    Trimmed,
+    /// The fixed wave lengths evenly spread over the whole range, at least two.
+    Linear(usize),
+    Uniform(usize),
+    Stratified(usize),
+    /// Proportionally to the response of the observer.
+    Importance(usize),
+}
+
+impl WaveLengthSampling {
+    /// `None` if the count is too small for the kind.
+    pub fn factory(&self, observer: &ColorMatching) -> Option<WaveLengthAnyFactory> {
+        match self {
+            &WaveLengthSampling::Trimmed => {
+                Some(WaveLengthAnyFactory::Trimmed(WaveLengthTrimmedFactory))
+            },
+            &WaveLengthSampling::Linear(count) if count > 1 => Some(WaveLengthAnyFactory::Linear(
+                WaveLengthLinearFactory::new(count),
+            )),
+            &WaveLengthSampling::Uniform(count) if count > 0 => Some(
+                WaveLengthAnyFactory::Uniform(WaveLengthUniformFactory::new(count)),
+            ),
+            &WaveLengthSampling::Stratified(count) if count > 0 => Some(
+                WaveLengthAnyFactory::Stratified(WaveLengthStratifiedFactory::new(count)),
+            ),
+            &WaveLengthSampling::Importance(count) if count > 0 => Some(
+                WaveLengthAnyFactory::Importance(WaveLengthImportanceFactory::new(count, observer)),
+            ),
+            _ => None,
+        }
+    }
+
+    /// The sampling of the factory with the identity, as in the state file, the importance
+    /// one is followed by the identity of the observer, see `ColorMatching::identity`.
+    pub fn from_identity(identity: &str) -> Option<Self> {
+        let mut words = identity.split(' ');
+        let kind = words.next()?;
+        let count = words.next()?.parse().ok()?;
+        let observer = words.next();
+        if words.next().is_some() {
+            return None;
+        }
+        match (kind, observer) {
+            ("trimmed", None) if count == 360 => Some(WaveLengthSampling::Trimmed),
+            ("linear", None) => Some(WaveLengthSampling::Linear(count)),
+            ("uniform", None) => Some(WaveLengthSampling::Uniform(count)),
+            ("stratified", None) => Some(WaveLengthSampling::Stratified(count)),
+            ("importance", Some(_)) => Some(WaveLengthSampling::Importance(count)),
+            _ => None,
+        }
+    }
+}
+
+/// Any of the factories, chosen when the scene is loaded.
+#[derive(Clone)]
+pub enum WaveLengthAnyFactory {
+    Trimmed(WaveLengthTrimmedFactory),
+    Linear(WaveLengthLinearFactory),
+    Uniform(WaveLengthUniformFactory),
+    Stratified(WaveLengthStratifiedFactory),
+    Importance(WaveLengthImportanceFactory),
+}
+
+pub enum WaveLengthAny {
+    Trimmed(WaveLengthTrimmed),
+    Linear(WaveLengthLinear),
+    Random(vec::IntoIter<(WaveLength, f64)>),
+}
+
+impl WaveLengthFactory for WaveLengthAnyFactory {
+    type Iter = WaveLengthAny;
+
+    fn iter<R>(&self, rng: &mut R) -> Self::Iter
+    where
+        R: Rng,
+    {
+        match self {
+            WaveLengthAnyFactory::Trimmed(factory) => WaveLengthAny::Trimmed(factory.iter(rng)),
+            WaveLengthAnyFactory::Linear(factory) => WaveLengthAny::Linear(factory.iter(rng)),
+            WaveLengthAnyFactory::Uniform(factory) => WaveLengthAny::Random(factory.iter(rng)),
+            WaveLengthAnyFactory::Stratified(factory) => WaveLengthAny::Random(factory.iter(rng)),
+            WaveLengthAnyFactory::Importance(factory) => WaveLengthAny::Random(factory.iter(rng)),
+        }
+    }
+
+    fn resolution(&self) -> usize {
+        match self {
+            WaveLengthAnyFactory::Trimmed(factory) => factory.resolution(),
+            WaveLengthAnyFactory::Linear(factory) => factory.resolution(),
+            WaveLengthAnyFactory::Uniform(factory) => factory.resolution(),
+            WaveLengthAnyFactory::Stratified(factory) => factory.resolution(),
+            WaveLengthAnyFactory::Importance(factory) => factory.resolution(),
+        }
+    }
+
+    fn identity(&self) -> String {
+        match self {
+            WaveLengthAnyFactory::Trimmed(factory) => factory.identity(),
+            WaveLengthAnyFactory::Linear(factory) => factory.identity(),
+            WaveLengthAnyFactory::Uniform(factory) => factory.identity(),
+            WaveLengthAnyFactory::Stratified(factory) => factory.identity(),
+            WaveLengthAnyFactory::Importance(factory) => factory.identity(),
+        }
+    }
+}
+
+impl Iterator for WaveLengthAny {
+    type Item = (WaveLength, f64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            WaveLengthAny::Trimmed(iter) => iter.next(),
+            WaveLengthAny::Linear(iter) => iter.next(),
+            WaveLengthAny::Random(iter) => iter.next(),
         }
     }
 }
 
 #[rustfmt::skip]
-const TABLE: [(f64, Rgb); 471] = [
-    (360.0, Rgb{ r: 0.000000122200, g: 0.000000013398, b: 0.000000535027 }),
-    (361.0, Rgb{ r: 0.000000185138, g: 0.000000020294, b: 0.000000810720 }),
-    (362.0, Rgb{ r: 0.000000278830, g: 0.000000030560, b: 0.000001221200 }),
-    (363.0, Rgb{ r: 0.000000417470, g: 0.000000045740, b: 0.000001828700 }),
-    (364.0, Rgb{ r: 0.000000621330, g: 0.000000068050, b: 0.000002722200 }),
-    (365.0, Rgb{ r: 0.000000919270, g: 0.000000100650, b: 0.000004028300 }),
-    (366.0, Rgb{ r: 0.000001351980, g: 0.000000147980, b: 0.000005925700 }),
-    (367.0, Rgb{ r: 0.000001976540, g: 0.000000216270, b: 0.000008665100 }),
-    (368.0, Rgb{ r: 0.000002872500, g: 0.000000314200, b: 0.000012596000 }),
-    (369.0, Rgb{ r: 0.000004149500, g: 0.000000453700, b: 0.000018201000 }),
-    (370.0, Rgb{ r: 0.000005958600, g: 0.000000651100, b: 0.000026143700 }),
-    (371.0, Rgb{ r: 0.000008505600, g: 0.000000928800, b: 0.000037330000 }),
-    (372.0, Rgb{ r: 0.000012068600, g: 0.000001317500, b: 0.000052987000 }),
-    (373.0, Rgb{ r: 0.000017022600, g: 0.000001857200, b: 0.000074764000 }),
-    (374.0, Rgb{ r: 0.000023868000, g: 0.000002602000, b: 0.000104870000 }),
-    (375.0, Rgb{ r: 0.000033266000, g: 0.000003625000, b: 0.000146220000 }),
-    (376.0, Rgb{ r: 0.000046087000, g: 0.000005019000, b: 0.000202660000 }),
-    (377.0, Rgb{ r: 0.000063472000, g: 0.000006907000, b: 0.000279230000 }),
-    (378.0, Rgb{ r: 0.000086892000, g: 0.000009449000, b: 0.000382450000 }),
-    (379.0, Rgb{ r: 0.000118246000, g: 0.000012848000, b: 0.000520720000 }),
-    (380.0, Rgb{ r: 0.000159952000, g: 0.000017364000, b: 0.000704776000 }),
-    (381.0, Rgb{ r: 0.000215080000, g: 0.000023327000, b: 0.000948230000 }),
-    (382.0, Rgb{ r: 0.000287490000, g: 0.000031150000, b: 0.001268200000 }),
-    (383.0, Rgb{ r: 0.000381990000, g: 0.000041350000, b: 0.001686100000 }),
-    (384.0, Rgb{ r: 0.000504550000, g: 0.000054560000, b: 0.002228500000 }),
-    (385.0, Rgb{ r: 0.000662440000, g: 0.000071560000, b: 0.002927800000 }),
-    (386.0, Rgb{ r: 0.000864500000, g: 0.000093300000, b: 0.003823700000 }),
-    (387.0, Rgb{ r: 0.001121500000, g: 0.000120870000, b: 0.004964200000 }),
-    (388.0, Rgb{ r: 0.001446160000, g: 0.000155640000, b: 0.006406700000 }),
-    (389.0, Rgb{ r: 0.001853590000, g: 0.000199200000, b: 0.008219300000 }),
-    (390.0, Rgb{ r: 0.002361600000, g: 0.000253400000, b: 0.010482200000 }),
-    (391.0, Rgb{ r: 0.002990600000, g: 0.000320200000, b: 0.013289000000 }),
-    (392.0, Rgb{ r: 0.003764500000, g: 0.000402400000, b: 0.016747000000 }),
-    (393.0, Rgb{ r: 0.004710200000, g: 0.000502300000, b: 0.020980000000 }),
-    (394.0, Rgb{ r: 0.005858100000, g: 0.000623200000, b: 0.026127000000 }),
-    (395.0, Rgb{ r: 0.007242300000, g: 0.000768500000, b: 0.032344000000 }),
-    (396.0, Rgb{ r: 0.008899600000, g: 0.000941700000, b: 0.039802000000 }),
-    (397.0, Rgb{ r: 0.010870900000, g: 0.001147800000, b: 0.048691000000 }),
-    (398.0, Rgb{ r: 0.013198900000, g: 0.001390300000, b: 0.059210000000 }),
-    (399.0, Rgb{ r: 0.015929200000, g: 0.001674000000, b: 0.071576000000 }),
-    (400.0, Rgb{ r: 0.019109700000, g: 0.002004400000, b: 0.086010900000 }),
-    (401.0, Rgb{ r: 0.022788000000, g: 0.002386000000, b: 0.102740000000 }),
-    (402.0, Rgb{ r: 0.027011000000, g: 0.002822000000, b: 0.122000000000 }),
-    (403.0, Rgb{ r: 0.031829000000, g: 0.003319000000, b: 0.144020000000 }),
-    (404.0, Rgb{ r: 0.037278000000, g: 0.003880000000, b: 0.168990000000 }),
-    (405.0, Rgb{ r: 0.043400000000, g: 0.004509000000, b: 0.197120000000 }),
-    (406.0, Rgb{ r: 0.050223000000, g: 0.005209000000, b: 0.228570000000 }),
-    (407.0, Rgb{ r: 0.057764000000, g: 0.005985000000, b: 0.263470000000 }),
-    (408.0, Rgb{ r: 0.066038000000, g: 0.006833000000, b: 0.301900000000 }),
-    (409.0, Rgb{ r: 0.075033000000, g: 0.007757000000, b: 0.343870000000 }),
-    (410.0, Rgb{ r: 0.084736000000, g: 0.008756000000, b: 0.389366000000 }),
-    (411.0, Rgb{ r: 0.095041000000, g: 0.009816000000, b: 0.437970000000 }),
-    (412.0, Rgb{ r: 0.105836000000, g: 0.010918000000, b: 0.489220000000 }),
-    (413.0, Rgb{ r: 0.117066000000, g: 0.012058000000, b: 0.542900000000 }),
-    (414.0, Rgb{ r: 0.128682000000, g: 0.013237000000, b: 0.598810000000 }),
-    (415.0, Rgb{ r: 0.140638000000, g: 0.014456000000, b: 0.656760000000 }),
-    (416.0, Rgb{ r: 0.152893000000, g: 0.015717000000, b: 0.716580000000 }),
-    (417.0, Rgb{ r: 0.165416000000, g: 0.017025000000, b: 0.778120000000 }),
-    (418.0, Rgb{ r: 0.178191000000, g: 0.018399000000, b: 0.841310000000 }),
-    (419.0, Rgb{ r: 0.191214000000, g: 0.019848000000, b: 0.906110000000 }),
-    (420.0, Rgb{ r: 0.204492000000, g: 0.021391000000, b: 0.972542000000 }),
-    (421.0, Rgb{ r: 0.217650000000, g: 0.022992000000, b: 1.038900000000 }),
-    (422.0, Rgb{ r: 0.230267000000, g: 0.024598000000, b: 1.103100000000 }),
-    (423.0, Rgb{ r: 0.242311000000, g: 0.026213000000, b: 1.165100000000 }),
-    (424.0, Rgb{ r: 0.253793000000, g: 0.027841000000, b: 1.224900000000 }),
-    (425.0, Rgb{ r: 0.264737000000, g: 0.029497000000, b: 1.282500000000 }),
-    (426.0, Rgb{ r: 0.275195000000, g: 0.031195000000, b: 1.338200000000 }),
-    (427.0, Rgb{ r: 0.285301000000, g: 0.032927000000, b: 1.392600000000 }),
-    (428.0, Rgb{ r: 0.295143000000, g: 0.034738000000, b: 1.446100000000 }),
-    (429.0, Rgb{ r: 0.304869000000, g: 0.036654000000, b: 1.499400000000 }),
-    (430.0, Rgb{ r: 0.314679000000, g: 0.038676000000, b: 1.553480000000 }),
-    (431.0, Rgb{ r: 0.324355000000, g: 0.040792000000, b: 1.607200000000 }),
-    (432.0, Rgb{ r: 0.333570000000, g: 0.042946000000, b: 1.658900000000 }),
-    (433.0, Rgb{ r: 0.342243000000, g: 0.045114000000, b: 1.708200000000 }),
-    (434.0, Rgb{ r: 0.350312000000, g: 0.047333000000, b: 1.754800000000 }),
-    (435.0, Rgb{ r: 0.357719000000, g: 0.049602000000, b: 1.798500000000 }),
-    (436.0, Rgb{ r: 0.364482000000, g: 0.051934000000, b: 1.839200000000 }),
-    (437.0, Rgb{ r: 0.370493000000, g: 0.054337000000, b: 1.876600000000 }),
-    (438.0, Rgb{ r: 0.375727000000, g: 0.056822000000, b: 1.910500000000 }),
-    (439.0, Rgb{ r: 0.380158000000, g: 0.059399000000, b: 1.940800000000 }),
-    (440.0, Rgb{ r: 0.383734000000, g: 0.062077000000, b: 1.967280000000 }),
-    (441.0, Rgb{ r: 0.386327000000, g: 0.064737000000, b: 1.989100000000 }),
-    (442.0, Rgb{ r: 0.387858000000, g: 0.067285000000, b: 2.005700000000 }),
-    (443.0, Rgb{ r: 0.388396000000, g: 0.069764000000, b: 2.017400000000 }),
-    (444.0, Rgb{ r: 0.387978000000, g: 0.072218000000, b: 2.024400000000 }),
-    (445.0, Rgb{ r: 0.386726000000, g: 0.074704000000, b: 2.027300000000 }),
-    (446.0, Rgb{ r: 0.384696000000, g: 0.077272000000, b: 2.026400000000 }),
-    (447.0, Rgb{ r: 0.382006000000, g: 0.079979000000, b: 2.022300000000 }),
-    (448.0, Rgb{ r: 0.378709000000, g: 0.082874000000, b: 2.015300000000 }),
-    (449.0, Rgb{ r: 0.374915000000, g: 0.086000000000, b: 2.006000000000 }),
-    (450.0, Rgb{ r: 0.370702000000, g: 0.089456000000, b: 1.994800000000 }),
-    (451.0, Rgb{ r: 0.366089000000, g: 0.092947000000, b: 1.981400000000 }),
-    (452.0, Rgb{ r: 0.361045000000, g: 0.096275000000, b: 1.965300000000 }),
-    (453.0, Rgb{ r: 0.355518000000, g: 0.099535000000, b: 1.946400000000 }),
-    (454.0, Rgb{ r: 0.349486000000, g: 0.102829000000, b: 1.924800000000 }),
-    (455.0, Rgb{ r: 0.342957000000, g: 0.106256000000, b: 1.900700000000 }),
-    (456.0, Rgb{ r: 0.335893000000, g: 0.109901000000, b: 1.874100000000 }),
-    (457.0, Rgb{ r: 0.328284000000, g: 0.113835000000, b: 1.845100000000 }),
-    (458.0, Rgb{ r: 0.320150000000, g: 0.118167000000, b: 1.813900000000 }),
-    (459.0, Rgb{ r: 0.311475000000, g: 0.122932000000, b: 1.780600000000 }),
-    (460.0, Rgb{ r: 0.302273000000, g: 0.128201000000, b: 1.745370000000 }),
-    (461.0, Rgb{ r: 0.292858000000, g: 0.133457000000, b: 1.709100000000 }),
-    (462.0, Rgb{ r: 0.283502000000, g: 0.138323000000, b: 1.672300000000 }),
-    (463.0, Rgb{ r: 0.274044000000, g: 0.143042000000, b: 1.634700000000 }),
-    (464.0, Rgb{ r: 0.264263000000, g: 0.147787000000, b: 1.595600000000 }),
-    (465.0, Rgb{ r: 0.254085000000, g: 0.152761000000, b: 1.554900000000 }),
-    (466.0, Rgb{ r: 0.243392000000, g: 0.158102000000, b: 1.512200000000 }),
-    (467.0, Rgb{ r: 0.232187000000, g: 0.163941000000, b: 1.467300000000 }),
-    (468.0, Rgb{ r: 0.220488000000, g: 0.170362000000, b: 1.419900000000 }),
-    (469.0, Rgb{ r: 0.208198000000, g: 0.177425000000, b: 1.370000000000 }),
-    (470.0, Rgb{ r: 0.195618000000, g: 0.185190000000, b: 1.317560000000 }),
-    (471.0, Rgb{ r: 0.183034000000, g: 0.193025000000, b: 1.262400000000 }),
-    (472.0, Rgb{ r: 0.170222000000, g: 0.200313000000, b: 1.205000000000 }),
-    (473.0, Rgb{ r: 0.157348000000, g: 0.207156000000, b: 1.146600000000 }),
-    (474.0, Rgb{ r: 0.144650000000, g: 0.213644000000, b: 1.088000000000 }),
-    (475.0, Rgb{ r: 0.132349000000, g: 0.219940000000, b: 1.030200000000 }),
-    (476.0, Rgb{ r: 0.120584000000, g: 0.226170000000, b: 0.973830000000 }),
-    (477.0, Rgb{ r: 0.109456000000, g: 0.232467000000, b: 0.919430000000 }),
-    (478.0, Rgb{ r: 0.099042000000, g: 0.239025000000, b: 0.867460000000 }),
-    (479.0, Rgb{ r: 0.089388000000, g: 0.245997000000, b: 0.818280000000 }),
-    (480.0, Rgb{ r: 0.080507000000, g: 0.253589000000, b: 0.772125000000 }),
-    (481.0, Rgb{ r: 0.072034000000, g: 0.261876000000, b: 0.728290000000 }),
-    (482.0, Rgb{ r: 0.063710000000, g: 0.270643000000, b: 0.686040000000 }),
-    (483.0, Rgb{ r: 0.055694000000, g: 0.279645000000, b: 0.645530000000 }),
-    (484.0, Rgb{ r: 0.048117000000, g: 0.288694000000, b: 0.606850000000 }),
-    (485.0, Rgb{ r: 0.041072000000, g: 0.297665000000, b: 0.570060000000 }),
-    (486.0, Rgb{ r: 0.034642000000, g: 0.306469000000, b: 0.535220000000 }),
-    (487.0, Rgb{ r: 0.028896000000, g: 0.315035000000, b: 0.502340000000 }),
-    (488.0, Rgb{ r: 0.023876000000, g: 0.323335000000, b: 0.471400000000 }),
-    (489.0, Rgb{ r: 0.019628000000, g: 0.331366000000, b: 0.442390000000 }),
-    (490.0, Rgb{ r: 0.016172000000, g: 0.339133000000, b: 0.415254000000 }),
-    (491.0, Rgb{ r: 0.013300000000, g: 0.347860000000, b: 0.390024000000 }),
-    (492.0, Rgb{ r: 0.010759000000, g: 0.358326000000, b: 0.366399000000 }),
-    (493.0, Rgb{ r: 0.008542000000, g: 0.370001000000, b: 0.344015000000 }),
-    (494.0, Rgb{ r: 0.006661000000, g: 0.382464000000, b: 0.322689000000 }),
-    (495.0, Rgb{ r: 0.005132000000, g: 0.395379000000, b: 0.302356000000 }),
-    (496.0, Rgb{ r: 0.003982000000, g: 0.408482000000, b: 0.283036000000 }),
-    (497.0, Rgb{ r: 0.003239000000, g: 0.421588000000, b: 0.264816000000 }),
-    (498.0, Rgb{ r: 0.002934000000, g: 0.434619000000, b: 0.247848000000 }),
-    (499.0, Rgb{ r: 0.003114000000, g: 0.447601000000, b: 0.232318000000 }),
-    (500.0, Rgb{ r: 0.003816000000, g: 0.460777000000, b: 0.218502000000 }),
-    (501.0, Rgb{ r: 0.005095000000, g: 0.474340000000, b: 0.205851000000 }),
-    (502.0, Rgb{ r: 0.006936000000, g: 0.488200000000, b: 0.193596000000 }),
-    (503.0, Rgb{ r: 0.009299000000, g: 0.502340000000, b: 0.181736000000 }),
-    (504.0, Rgb{ r: 0.012147000000, g: 0.516740000000, b: 0.170281000000 }),
-    (505.0, Rgb{ r: 0.015444000000, g: 0.531360000000, b: 0.159249000000 }),
-    (506.0, Rgb{ r: 0.019156000000, g: 0.546190000000, b: 0.148673000000 }),
-    (507.0, Rgb{ r: 0.023250000000, g: 0.561180000000, b: 0.138609000000 }),
-    (508.0, Rgb{ r: 0.027690000000, g: 0.576290000000, b: 0.129096000000 }),
-    (509.0, Rgb{ r: 0.032444000000, g: 0.591500000000, b: 0.120215000000 }),
-    (510.0, Rgb{ r: 0.037465000000, g: 0.606741000000, b: 0.112044000000 }),
-    (511.0, Rgb{ r: 0.042956000000, g: 0.622150000000, b: 0.104710000000 }),
-    (512.0, Rgb{ r: 0.049114000000, g: 0.637830000000, b: 0.098196000000 }),
-    (513.0, Rgb{ r: 0.055920000000, g: 0.653710000000, b: 0.092361000000 }),
-    (514.0, Rgb{ r: 0.063349000000, g: 0.669680000000, b: 0.087088000000 }),
-    (515.0, Rgb{ r: 0.071358000000, g: 0.685660000000, b: 0.082248000000 }),
-    (516.0, Rgb{ r: 0.079901000000, g: 0.701550000000, b: 0.077744000000 }),
-    (517.0, Rgb{ r: 0.088909000000, g: 0.717230000000, b: 0.073456000000 }),
-    (518.0, Rgb{ r: 0.098293000000, g: 0.732570000000, b: 0.069268000000 }),
-    (519.0, Rgb{ r: 0.107949000000, g: 0.747460000000, b: 0.065060000000 }),
-    (520.0, Rgb{ r: 0.117749000000, g: 0.761757000000, b: 0.060709000000 }),
-    (521.0, Rgb{ r: 0.127839000000, g: 0.775340000000, b: 0.056457000000 }),
-    (522.0, Rgb{ r: 0.138450000000, g: 0.788220000000, b: 0.052609000000 }),
-    (523.0, Rgb{ r: 0.149516000000, g: 0.800460000000, b: 0.049122000000 }),
-    (524.0, Rgb{ r: 0.161041000000, g: 0.812140000000, b: 0.045954000000 }),
-    (525.0, Rgb{ r: 0.172953000000, g: 0.823330000000, b: 0.043050000000 }),
-    (526.0, Rgb{ r: 0.185209000000, g: 0.834120000000, b: 0.040368000000 }),
-    (527.0, Rgb{ r: 0.197755000000, g: 0.844600000000, b: 0.037839000000 }),
-    (528.0, Rgb{ r: 0.210538000000, g: 0.854870000000, b: 0.035384000000 }),
-    (529.0, Rgb{ r: 0.223460000000, g: 0.865040000000, b: 0.032949000000 }),
-    (530.0, Rgb{ r: 0.236491000000, g: 0.875211000000, b: 0.030451000000 }),
-    (531.0, Rgb{ r: 0.249633000000, g: 0.885370000000, b: 0.028029000000 }),
-    (532.0, Rgb{ r: 0.262972000000, g: 0.895370000000, b: 0.025862000000 }),
-    (533.0, Rgb{ r: 0.276515000000, g: 0.905150000000, b: 0.023920000000 }),
-    (534.0, Rgb{ r: 0.290269000000, g: 0.914650000000, b: 0.022174000000 }),
-    (535.0, Rgb{ r: 0.304213000000, g: 0.923810000000, b: 0.020584000000 }),
-    (536.0, Rgb{ r: 0.318361000000, g: 0.932550000000, b: 0.019127000000 }),
-    (537.0, Rgb{ r: 0.332705000000, g: 0.940810000000, b: 0.017740000000 }),
-    (538.0, Rgb{ r: 0.347232000000, g: 0.948520000000, b: 0.016403000000 }),
-    (539.0, Rgb{ r: 0.361926000000, g: 0.955600000000, b: 0.015064000000 }),
-    (540.0, Rgb{ r: 0.376772000000, g: 0.961988000000, b: 0.013676000000 }),
-    (541.0, Rgb{ r: 0.391683000000, g: 0.967540000000, b: 0.012308000000 }),
-    (542.0, Rgb{ r: 0.406594000000, g: 0.972230000000, b: 0.011056000000 }),
-    (543.0, Rgb{ r: 0.421539000000, g: 0.976170000000, b: 0.009915000000 }),
-    (544.0, Rgb{ r: 0.436517000000, g: 0.979460000000, b: 0.008872000000 }),
-    (545.0, Rgb{ r: 0.451584000000, g: 0.982200000000, b: 0.007918000000 }),
-    (546.0, Rgb{ r: 0.466782000000, g: 0.984520000000, b: 0.007030000000 }),
-    (547.0, Rgb{ r: 0.482147000000, g: 0.986520000000, b: 0.006223000000 }),
-    (548.0, Rgb{ r: 0.497738000000, g: 0.988320000000, b: 0.005453000000 }),
-    (549.0, Rgb{ r: 0.513606000000, g: 0.990020000000, b: 0.004714000000 }),
-    (550.0, Rgb{ r: 0.529826000000, g: 0.991761000000, b: 0.003988000000 }),
-    (551.0, Rgb{ r: 0.546440000000, g: 0.993530000000, b: 0.003289000000 }),
-    (552.0, Rgb{ r: 0.563426000000, g: 0.995230000000, b: 0.002646000000 }),
-    (553.0, Rgb{ r: 0.580726000000, g: 0.996770000000, b: 0.002063000000 }),
-    (554.0, Rgb{ r: 0.598290000000, g: 0.998090000000, b: 0.001533000000 }),
-    (555.0, Rgb{ r: 0.616053000000, g: 0.999110000000, b: 0.001091000000 }),
-    (556.0, Rgb{ r: 0.633948000000, g: 0.999770000000, b: 0.000711000000 }),
-    (557.0, Rgb{ r: 0.651901000000, g: 1.000000000000, b: 0.000407000000 }),
-    (558.0, Rgb{ r: 0.669824000000, g: 0.999710000000, b: 0.000184000000 }),
-    (559.0, Rgb{ r: 0.687632000000, g: 0.998850000000, b: 0.000047000000 }),
-    (560.0, Rgb{ r: 0.705224000000, g: 0.997340000000, b: 0.000000000000 }),
-    (561.0, Rgb{ r: 0.722773000000, g: 0.995260000000, b: 0.000000000000 }),
-    (562.0, Rgb{ r: 0.740483000000, g: 0.992740000000, b: 0.000000000000 }),
-    (563.0, Rgb{ r: 0.758273000000, g: 0.989750000000, b: 0.000000000000 }),
-    (564.0, Rgb{ r: 0.776083000000, g: 0.986300000000, b: 0.000000000000 }),
-    (565.0, Rgb{ r: 0.793832000000, g: 0.982380000000, b: 0.000000000000 }),
-    (566.0, Rgb{ r: 0.811436000000, g: 0.977980000000, b: 0.000000000000 }),
-    (567.0, Rgb{ r: 0.828822000000, g: 0.973110000000, b: 0.000000000000 }),
-    (568.0, Rgb{ r: 0.845879000000, g: 0.967740000000, b: 0.000000000000 }),
-    (569.0, Rgb{ r: 0.862525000000, g: 0.961890000000, b: 0.000000000000 }),
-    (570.0, Rgb{ r: 0.878655000000, g: 0.955552000000, b: 0.000000000000 }),
-    (571.0, Rgb{ r: 0.894208000000, g: 0.948601000000, b: 0.000000000000 }),
-    (572.0, Rgb{ r: 0.909206000000, g: 0.940981000000, b: 0.000000000000 }),
-    (573.0, Rgb{ r: 0.923672000000, g: 0.932798000000, b: 0.000000000000 }),
-    (574.0, Rgb{ r: 0.937638000000, g: 0.924158000000, b: 0.000000000000 }),
-    (575.0, Rgb{ r: 0.951162000000, g: 0.915175000000, b: 0.000000000000 }),
-    (576.0, Rgb{ r: 0.964283000000, g: 0.905954000000, b: 0.000000000000 }),
-    (577.0, Rgb{ r: 0.977068000000, g: 0.896608000000, b: 0.000000000000 }),
-    (578.0, Rgb{ r: 0.989590000000, g: 0.887249000000, b: 0.000000000000 }),
-    (579.0, Rgb{ r: 1.001910000000, g: 0.877986000000, b: 0.000000000000 }),
-    (580.0, Rgb{ r: 1.014160000000, g: 0.868934000000, b: 0.000000000000 }),
-    (581.0, Rgb{ r: 1.026500000000, g: 0.860164000000, b: 0.000000000000 }),
-    (582.0, Rgb{ r: 1.038800000000, g: 0.851519000000, b: 0.000000000000 }),
-    (583.0, Rgb{ r: 1.051000000000, g: 0.842963000000, b: 0.000000000000 }),
-    (584.0, Rgb{ r: 1.062900000000, g: 0.834393000000, b: 0.000000000000 }),
-    (585.0, Rgb{ r: 1.074300000000, g: 0.825623000000, b: 0.000000000000 }),
-    (586.0, Rgb{ r: 1.085200000000, g: 0.816764000000, b: 0.000000000000 }),
-    (587.0, Rgb{ r: 1.095200000000, g: 0.807544000000, b: 0.000000000000 }),
-    (588.0, Rgb{ r: 1.104200000000, g: 0.797947000000, b: 0.000000000000 }),
-    (589.0, Rgb{ r: 1.112000000000, g: 0.787893000000, b: 0.000000000000 }),
-    (590.0, Rgb{ r: 1.118520000000, g: 0.777405000000, b: 0.000000000000 }),
-    (591.0, Rgb{ r: 1.123800000000, g: 0.766490000000, b: 0.000000000000 }),
-    (592.0, Rgb{ r: 1.128000000000, g: 0.755309000000, b: 0.000000000000 }),
-    (593.0, Rgb{ r: 1.131100000000, g: 0.743845000000, b: 0.000000000000 }),
-    (594.0, Rgb{ r: 1.133200000000, g: 0.732190000000, b: 0.000000000000 }),
-    (595.0, Rgb{ r: 1.134300000000, g: 0.720353000000, b: 0.000000000000 }),
-    (596.0, Rgb{ r: 1.134300000000, g: 0.708281000000, b: 0.000000000000 }),
-    (597.0, Rgb{ r: 1.133300000000, g: 0.696055000000, b: 0.000000000000 }),
-    (598.0, Rgb{ r: 1.131200000000, g: 0.683621000000, b: 0.000000000000 }),
-    (599.0, Rgb{ r: 1.128100000000, g: 0.671048000000, b: 0.000000000000 }),
-    (600.0, Rgb{ r: 1.123990000000, g: 0.658341000000, b: 0.000000000000 }),
-    (601.0, Rgb{ r: 1.118900000000, g: 0.645545000000, b: 0.000000000000 }),
-    (602.0, Rgb{ r: 1.112900000000, g: 0.632718000000, b: 0.000000000000 }),
-    (603.0, Rgb{ r: 1.105900000000, g: 0.619815000000, b: 0.000000000000 }),
-    (604.0, Rgb{ r: 1.098000000000, g: 0.606887000000, b: 0.000000000000 }),
-    (605.0, Rgb{ r: 1.089100000000, g: 0.593878000000, b: 0.000000000000 }),
-    (606.0, Rgb{ r: 1.079200000000, g: 0.580781000000, b: 0.000000000000 }),
-    (607.0, Rgb{ r: 1.068400000000, g: 0.567653000000, b: 0.000000000000 }),
-    (608.0, Rgb{ r: 1.056700000000, g: 0.554490000000, b: 0.000000000000 }),
-    (609.0, Rgb{ r: 1.044000000000, g: 0.541228000000, b: 0.000000000000 }),
-    (610.0, Rgb{ r: 1.030480000000, g: 0.527963000000, b: 0.000000000000 }),
-    (611.0, Rgb{ r: 1.016000000000, g: 0.514634000000, b: 0.000000000000 }),
-    (612.0, Rgb{ r: 1.000800000000, g: 0.501363000000, b: 0.000000000000 }),
-    (613.0, Rgb{ r: 0.984790000000, g: 0.488124000000, b: 0.000000000000 }),
-    (614.0, Rgb{ r: 0.968080000000, g: 0.474935000000, b: 0.000000000000 }),
-    (615.0, Rgb{ r: 0.950740000000, g: 0.461834000000, b: 0.000000000000 }),
-    (616.0, Rgb{ r: 0.932800000000, g: 0.448823000000, b: 0.000000000000 }),
-    (617.0, Rgb{ r: 0.914340000000, g: 0.435917000000, b: 0.000000000000 }),
-    (618.0, Rgb{ r: 0.895390000000, g: 0.423153000000, b: 0.000000000000 }),
-    (619.0, Rgb{ r: 0.876030000000, g: 0.410526000000, b: 0.000000000000 }),
-    (620.0, Rgb{ r: 0.856297000000, g: 0.398057000000, b: 0.000000000000 }),
-    (621.0, Rgb{ r: 0.836350000000, g: 0.385835000000, b: 0.000000000000 }),
-    (622.0, Rgb{ r: 0.816290000000, g: 0.373951000000, b: 0.000000000000 }),
-    (623.0, Rgb{ r: 0.796050000000, g: 0.362311000000, b: 0.000000000000 }),
-    (624.0, Rgb{ r: 0.775610000000, g: 0.350863000000, b: 0.000000000000 }),
-    (625.0, Rgb{ r: 0.754930000000, g: 0.339554000000, b: 0.000000000000 }),
-    (626.0, Rgb{ r: 0.733990000000, g: 0.328309000000, b: 0.000000000000 }),
-    (627.0, Rgb{ r: 0.712780000000, g: 0.317118000000, b: 0.000000000000 }),
-    (628.0, Rgb{ r: 0.691290000000, g: 0.305936000000, b: 0.000000000000 }),
-    (629.0, Rgb{ r: 0.669520000000, g: 0.294737000000, b: 0.000000000000 }),
-    (630.0, Rgb{ r: 0.647467000000, g: 0.283493000000, b: 0.000000000000 }),
-    (631.0, Rgb{ r: 0.625110000000, g: 0.272222000000, b: 0.000000000000 }),
-    (632.0, Rgb{ r: 0.602520000000, g: 0.260990000000, b: 0.000000000000 }),
-    (633.0, Rgb{ r: 0.579890000000, g: 0.249877000000, b: 0.000000000000 }),
-    (634.0, Rgb{ r: 0.557370000000, g: 0.238946000000, b: 0.000000000000 }),
-    (635.0, Rgb{ r: 0.535110000000, g: 0.228254000000, b: 0.000000000000 }),
-    (636.0, Rgb{ r: 0.513240000000, g: 0.217853000000, b: 0.000000000000 }),
-    (637.0, Rgb{ r: 0.491860000000, g: 0.207780000000, b: 0.000000000000 }),
-    (638.0, Rgb{ r: 0.471080000000, g: 0.198072000000, b: 0.000000000000 }),
-    (639.0, Rgb{ r: 0.450960000000, g: 0.188748000000, b: 0.000000000000 }),
-    (640.0, Rgb{ r: 0.431567000000, g: 0.179828000000, b: 0.000000000000 }),
-    (641.0, Rgb{ r: 0.412870000000, g: 0.171285000000, b: 0.000000000000 }),
-    (642.0, Rgb{ r: 0.394750000000, g: 0.163059000000, b: 0.000000000000 }),
-    (643.0, Rgb{ r: 0.377210000000, g: 0.155151000000, b: 0.000000000000 }),
-    (644.0, Rgb{ r: 0.360190000000, g: 0.147535000000, b: 0.000000000000 }),
-    (645.0, Rgb{ r: 0.343690000000, g: 0.140211000000, b: 0.000000000000 }),
-    (646.0, Rgb{ r: 0.327690000000, g: 0.133170000000, b: 0.000000000000 }),
-    (647.0, Rgb{ r: 0.312170000000, g: 0.126400000000, b: 0.000000000000 }),
-    (648.0, Rgb{ r: 0.297110000000, g: 0.119892000000, b: 0.000000000000 }),
-    (649.0, Rgb{ r: 0.282500000000, g: 0.113640000000, b: 0.000000000000 }),
-    (650.0, Rgb{ r: 0.268329000000, g: 0.107633000000, b: 0.000000000000 }),
-    (651.0, Rgb{ r: 0.254590000000, g: 0.101870000000, b: 0.000000000000 }),
-    (652.0, Rgb{ r: 0.241300000000, g: 0.096347000000, b: 0.000000000000 }),
-    (653.0, Rgb{ r: 0.228480000000, g: 0.091063000000, b: 0.000000000000 }),
-    (654.0, Rgb{ r: 0.216140000000, g: 0.086010000000, b: 0.000000000000 }),
-    (655.0, Rgb{ r: 0.204300000000, g: 0.081187000000, b: 0.000000000000 }),
-    (656.0, Rgb{ r: 0.192950000000, g: 0.076583000000, b: 0.000000000000 }),
-    (657.0, Rgb{ r: 0.182110000000, g: 0.072198000000, b: 0.000000000000 }),
-    (658.0, Rgb{ r: 0.171770000000, g: 0.068024000000, b: 0.000000000000 }),
-    (659.0, Rgb{ r: 0.161920000000, g: 0.064052000000, b: 0.000000000000 }),
-    (660.0, Rgb{ r: 0.152568000000, g: 0.060281000000, b: 0.000000000000 }),
-    (661.0, Rgb{ r: 0.143670000000, g: 0.056697000000, b: 0.000000000000 }),
-    (662.0, Rgb{ r: 0.135200000000, g: 0.053292000000, b: 0.000000000000 }),
-    (663.0, Rgb{ r: 0.127130000000, g: 0.050059000000, b: 0.000000000000 }),
-    (664.0, Rgb{ r: 0.119480000000, g: 0.046998000000, b: 0.000000000000 }),
-    (665.0, Rgb{ r: 0.112210000000, g: 0.044096000000, b: 0.000000000000 }),
-    (666.0, Rgb{ r: 0.105310000000, g: 0.041345000000, b: 0.000000000000 }),
-    (667.0, Rgb{ r: 0.098786000000, g: 0.038750700000, b: 0.000000000000 }),
-    (668.0, Rgb{ r: 0.092610000000, g: 0.036297800000, b: 0.000000000000 }),
-    (669.0, Rgb{ r: 0.086773000000, g: 0.033983200000, b: 0.000000000000 }),
-    (670.0, Rgb{ r: 0.081260600000, g: 0.031800400000, b: 0.000000000000 }),
-    (671.0, Rgb{ r: 0.076048000000, g: 0.029739500000, b: 0.000000000000 }),
-    (672.0, Rgb{ r: 0.071114000000, g: 0.027791800000, b: 0.000000000000 }),
-    (673.0, Rgb{ r: 0.066454000000, g: 0.025955100000, b: 0.000000000000 }),
-    (674.0, Rgb{ r: 0.062062000000, g: 0.024226300000, b: 0.000000000000 }),
-    (675.0, Rgb{ r: 0.057930000000, g: 0.022601700000, b: 0.000000000000 }),
-    (676.0, Rgb{ r: 0.054050000000, g: 0.021077900000, b: 0.000000000000 }),
-    (677.0, Rgb{ r: 0.050412000000, g: 0.019650500000, b: 0.000000000000 }),
-    (678.0, Rgb{ r: 0.047006000000, g: 0.018315300000, b: 0.000000000000 }),
-    (679.0, Rgb{ r: 0.043823000000, g: 0.017068600000, b: 0.000000000000 }),
-    (680.0, Rgb{ r: 0.040850800000, g: 0.015905100000, b: 0.000000000000 }),
-    (681.0, Rgb{ r: 0.038072000000, g: 0.014818300000, b: 0.000000000000 }),
-    (682.0, Rgb{ r: 0.035468000000, g: 0.013800800000, b: 0.000000000000 }),
-    (683.0, Rgb{ r: 0.033031000000, g: 0.012849500000, b: 0.000000000000 }),
-    (684.0, Rgb{ r: 0.030753000000, g: 0.011960700000, b: 0.000000000000 }),
-    (685.0, Rgb{ r: 0.028623000000, g: 0.011130300000, b: 0.000000000000 }),
-    (686.0, Rgb{ r: 0.026635000000, g: 0.010355500000, b: 0.000000000000 }),
-    (687.0, Rgb{ r: 0.024781000000, g: 0.009633200000, b: 0.000000000000 }),
-    (688.0, Rgb{ r: 0.023052000000, g: 0.008959900000, b: 0.000000000000 }),
-    (689.0, Rgb{ r: 0.021441000000, g: 0.008332400000, b: 0.000000000000 }),
-    (690.0, Rgb{ r: 0.019941300000, g: 0.007748800000, b: 0.000000000000 }),
-    (691.0, Rgb{ r: 0.018544000000, g: 0.007204600000, b: 0.000000000000 }),
-    (692.0, Rgb{ r: 0.017241000000, g: 0.006697500000, b: 0.000000000000 }),
-    (693.0, Rgb{ r: 0.016027000000, g: 0.006225100000, b: 0.000000000000 }),
-    (694.0, Rgb{ r: 0.014896000000, g: 0.005785000000, b: 0.000000000000 }),
-    (695.0, Rgb{ r: 0.013842000000, g: 0.005375100000, b: 0.000000000000 }),
-    (696.0, Rgb{ r: 0.012862000000, g: 0.004994100000, b: 0.000000000000 }),
-    (697.0, Rgb{ r: 0.011949000000, g: 0.004639200000, b: 0.000000000000 }),
-    (698.0, Rgb{ r: 0.011100000000, g: 0.004309300000, b: 0.000000000000 }),
-    (699.0, Rgb{ r: 0.010311000000, g: 0.004002800000, b: 0.000000000000 }),
-    (700.0, Rgb{ r: 0.009576880000, g: 0.003717740000, b: 0.000000000000 }),
-    (701.0, Rgb{ r: 0.008894000000, g: 0.003452620000, b: 0.000000000000 }),
-    (702.0, Rgb{ r: 0.008258100000, g: 0.003205830000, b: 0.000000000000 }),
-    (703.0, Rgb{ r: 0.007666400000, g: 0.002976230000, b: 0.000000000000 }),
-    (704.0, Rgb{ r: 0.007116300000, g: 0.002762810000, b: 0.000000000000 }),
-    (705.0, Rgb{ r: 0.006605200000, g: 0.002564560000, b: 0.000000000000 }),
-    (706.0, Rgb{ r: 0.006130600000, g: 0.002380480000, b: 0.000000000000 }),
-    (707.0, Rgb{ r: 0.005690300000, g: 0.002209710000, b: 0.000000000000 }),
-    (708.0, Rgb{ r: 0.005281900000, g: 0.002051320000, b: 0.000000000000 }),
-    (709.0, Rgb{ r: 0.004903300000, g: 0.001904490000, b: 0.000000000000 }),
-    (710.0, Rgb{ r: 0.004552630000, g: 0.001768470000, b: 0.000000000000 }),
-    (711.0, Rgb{ r: 0.004227500000, g: 0.001642360000, b: 0.000000000000 }),
-    (712.0, Rgb{ r: 0.003925800000, g: 0.001525350000, b: 0.000000000000 }),
-    (713.0, Rgb{ r: 0.003645700000, g: 0.001416720000, b: 0.000000000000 }),
-    (714.0, Rgb{ r: 0.003385900000, g: 0.001315950000, b: 0.000000000000 }),
-    (715.0, Rgb{ r: 0.003144700000, g: 0.001222390000, b: 0.000000000000 }),
-    (716.0, Rgb{ r: 0.002920800000, g: 0.001135550000, b: 0.000000000000 }),
-    (717.0, Rgb{ r: 0.002713000000, g: 0.001054940000, b: 0.000000000000 }),
-    (718.0, Rgb{ r: 0.002520200000, g: 0.000980140000, b: 0.000000000000 }),
-    (719.0, Rgb{ r: 0.002341100000, g: 0.000910660000, b: 0.000000000000 }),
-    (720.0, Rgb{ r: 0.002174960000, g: 0.000846190000, b: 0.000000000000 }),
-    (721.0, Rgb{ r: 0.002020600000, g: 0.000786290000, b: 0.000000000000 }),
-    (722.0, Rgb{ r: 0.001877300000, g: 0.000730680000, b: 0.000000000000 }),
-    (723.0, Rgb{ r: 0.001744100000, g: 0.000678990000, b: 0.000000000000 }),
-    (724.0, Rgb{ r: 0.001620500000, g: 0.000631010000, b: 0.000000000000 }),
-    (725.0, Rgb{ r: 0.001505700000, g: 0.000586440000, b: 0.000000000000 }),
-    (726.0, Rgb{ r: 0.001399200000, g: 0.000545110000, b: 0.000000000000 }),
-    (727.0, Rgb{ r: 0.001300400000, g: 0.000506720000, b: 0.000000000000 }),
-    (728.0, Rgb{ r: 0.001208700000, g: 0.000471110000, b: 0.000000000000 }),
-    (729.0, Rgb{ r: 0.001123600000, g: 0.000438050000, b: 0.000000000000 }),
-    (730.0, Rgb{ r: 0.001044760000, g: 0.000407410000, b: 0.000000000000 }),
-    (731.0, Rgb{ r: 0.000971560000, g: 0.000378962000, b: 0.000000000000 }),
-    (732.0, Rgb{ r: 0.000903600000, g: 0.000352543000, b: 0.000000000000 }),
-    (733.0, Rgb{ r: 0.000840480000, g: 0.000328001000, b: 0.000000000000 }),
-    (734.0, Rgb{ r: 0.000781870000, g: 0.000305208000, b: 0.000000000000 }),
-    (735.0, Rgb{ r: 0.000727450000, g: 0.000284041000, b: 0.000000000000 }),
-    (736.0, Rgb{ r: 0.000676900000, g: 0.000264375000, b: 0.000000000000 }),
-    (737.0, Rgb{ r: 0.000629960000, g: 0.000246109000, b: 0.000000000000 }),
-    (738.0, Rgb{ r: 0.000586370000, g: 0.000229143000, b: 0.000000000000 }),
-    (739.0, Rgb{ r: 0.000545870000, g: 0.000213376000, b: 0.000000000000 }),
-    (740.0, Rgb{ r: 0.000508258000, g: 0.000198730000, b: 0.000000000000 }),
-    (741.0, Rgb{ r: 0.000473300000, g: 0.000185115000, b: 0.000000000000 }),
-    (742.0, Rgb{ r: 0.000440800000, g: 0.000172454000, b: 0.000000000000 }),
-    (743.0, Rgb{ r: 0.000410580000, g: 0.000160678000, b: 0.000000000000 }),
-    (744.0, Rgb{ r: 0.000382490000, g: 0.000149730000, b: 0.000000000000 }),
-    (745.0, Rgb{ r: 0.000356380000, g: 0.000139550000, b: 0.000000000000 }),
-    (746.0, Rgb{ r: 0.000332110000, g: 0.000130086000, b: 0.000000000000 }),
-    (747.0, Rgb{ r: 0.000309550000, g: 0.000121290000, b: 0.000000000000 }),
-    (748.0, Rgb{ r: 0.000288580000, g: 0.000113106000, b: 0.000000000000 }),
-    (749.0, Rgb{ r: 0.000269090000, g: 0.000105501000, b: 0.000000000000 }),
-    (750.0, Rgb{ r: 0.000250969000, g: 0.000098428000, b: 0.000000000000 }),
-    (751.0, Rgb{ r: 0.000234130000, g: 0.000091853000, b: 0.000000000000 }),
-    (752.0, Rgb{ r: 0.000218470000, g: 0.000085738000, b: 0.000000000000 }),
-    (753.0, Rgb{ r: 0.000203910000, g: 0.000080048000, b: 0.000000000000 }),
-    (754.0, Rgb{ r: 0.000190350000, g: 0.000074751000, b: 0.000000000000 }),
-    (755.0, Rgb{ r: 0.000177730000, g: 0.000069819000, b: 0.000000000000 }),
-    (756.0, Rgb{ r: 0.000165970000, g: 0.000065222000, b: 0.000000000000 }),
-    (757.0, Rgb{ r: 0.000155020000, g: 0.000060939000, b: 0.000000000000 }),
-    (758.0, Rgb{ r: 0.000144800000, g: 0.000056942000, b: 0.000000000000 }),
-    (759.0, Rgb{ r: 0.000135280000, g: 0.000053217000, b: 0.000000000000 }),
-    (760.0, Rgb{ r: 0.000126390000, g: 0.000049737000, b: 0.000000000000 }),
-    (761.0, Rgb{ r: 0.000118100000, g: 0.000046491000, b: 0.000000000000 }),
-    (762.0, Rgb{ r: 0.000110370000, g: 0.000043464000, b: 0.000000000000 }),
-    (763.0, Rgb{ r: 0.000103150000, g: 0.000040635000, b: 0.000000000000 }),
-    (764.0, Rgb{ r: 0.000096427000, g: 0.000038000000, b: 0.000000000000 }),
-    (765.0, Rgb{ r: 0.000090151000, g: 0.000035540500, b: 0.000000000000 }),
-    (766.0, Rgb{ r: 0.000084294000, g: 0.000033244800, b: 0.000000000000 }),
-    (767.0, Rgb{ r: 0.000078830000, g: 0.000031100600, b: 0.000000000000 }),
-    (768.0, Rgb{ r: 0.000073729000, g: 0.000029099000, b: 0.000000000000 }),
-    (769.0, Rgb{ r: 0.000068969000, g: 0.000027230700, b: 0.000000000000 }),
-    (770.0, Rgb{ r: 0.000064525800, g: 0.000025486000, b: 0.000000000000 }),
-    (771.0, Rgb{ r: 0.000060376000, g: 0.000023856100, b: 0.000000000000 }),
-    (772.0, Rgb{ r: 0.000056500000, g: 0.000022333200, b: 0.000000000000 }),
-    (773.0, Rgb{ r: 0.000052880000, g: 0.000020910400, b: 0.000000000000 }),
-    (774.0, Rgb{ r: 0.000049498000, g: 0.000019580800, b: 0.000000000000 }),
-    (775.0, Rgb{ r: 0.000046339000, g: 0.000018338400, b: 0.000000000000 }),
-    (776.0, Rgb{ r: 0.000043389000, g: 0.000017177700, b: 0.000000000000 }),
-    (777.0, Rgb{ r: 0.000040634000, g: 0.000016093400, b: 0.000000000000 }),
-    (778.0, Rgb{ r: 0.000038060000, g: 0.000015080000, b: 0.000000000000 }),
-    (779.0, Rgb{ r: 0.000035657000, g: 0.000014133600, b: 0.000000000000 }),
-    (780.0, Rgb{ r: 0.000033411700, g: 0.000013249000, b: 0.000000000000 }),
-    (781.0, Rgb{ r: 0.000031315000, g: 0.000012422600, b: 0.000000000000 }),
-    (782.0, Rgb{ r: 0.000029355000, g: 0.000011649900, b: 0.000000000000 }),
-    (783.0, Rgb{ r: 0.000027524000, g: 0.000010927700, b: 0.000000000000 }),
-    (784.0, Rgb{ r: 0.000025811000, g: 0.000010251900, b: 0.000000000000 }),
-    (785.0, Rgb{ r: 0.000024209000, g: 0.000009619600, b: 0.000000000000 }),
-    (786.0, Rgb{ r: 0.000022711000, g: 0.000009028100, b: 0.000000000000 }),
-    (787.0, Rgb{ r: 0.000021308000, g: 0.000008474000, b: 0.000000000000 }),
-    (788.0, Rgb{ r: 0.000019994000, g: 0.000007954800, b: 0.000000000000 }),
-    (789.0, Rgb{ r: 0.000018764000, g: 0.000007468600, b: 0.000000000000 }),
-    (790.0, Rgb{ r: 0.000017611500, g: 0.000007012800, b: 0.000000000000 }),
-    (791.0, Rgb{ r: 0.000016532000, g: 0.000006585800, b: 0.000000000000 }),
-    (792.0, Rgb{ r: 0.000015521000, g: 0.000006185700, b: 0.000000000000 }),
-    (793.0, Rgb{ r: 0.000014574000, g: 0.000005810700, b: 0.000000000000 }),
-    (794.0, Rgb{ r: 0.000013686000, g: 0.000005459000, b: 0.000000000000 }),
-    (795.0, Rgb{ r: 0.000012855000, g: 0.000005129800, b: 0.000000000000 }),
-    (796.0, Rgb{ r: 0.000012075000, g: 0.000004820600, b: 0.000000000000 }),
-    (797.0, Rgb{ r: 0.000011345000, g: 0.000004531200, b: 0.000000000000 }),
-    (798.0, Rgb{ r: 0.000010659000, g: 0.000004259100, b: 0.000000000000 }),
-    (799.0, Rgb{ r: 0.000010017000, g: 0.000004004200, b: 0.000000000000 }),
-    (800.0, Rgb{ r: 0.000009413630, g: 0.000003764730, b: 0.000000000000 }),
-    (801.0, Rgb{ r: 0.000008847900, g: 0.000003539950, b: 0.000000000000 }),
-    (802.0, Rgb{ r: 0.000008317100, g: 0.000003329140, b: 0.000000000000 }),
-    (803.0, Rgb{ r: 0.000007819000, g: 0.000003131150, b: 0.000000000000 }),
-    (804.0, Rgb{ r: 0.000007351600, g: 0.000002945290, b: 0.000000000000 }),
-    (805.0, Rgb{ r: 0.000006913000, g: 0.000002770810, b: 0.000000000000 }),
-    (806.0, Rgb{ r: 0.000006501500, g: 0.000002607050, b: 0.000000000000 }),
-    (807.0, Rgb{ r: 0.000006115300, g: 0.000002453290, b: 0.000000000000 }),
-    (808.0, Rgb{ r: 0.000005752900, g: 0.000002308940, b: 0.000000000000 }),
-    (809.0, Rgb{ r: 0.000005412700, g: 0.000002173380, b: 0.000000000000 }),
-    (810.0, Rgb{ r: 0.000005093470, g: 0.000002046130, b: 0.000000000000 }),
-    (811.0, Rgb{ r: 0.000004793800, g: 0.000001926620, b: 0.000000000000 }),
-    (812.0, Rgb{ r: 0.000004512500, g: 0.000001814400, b: 0.000000000000 }),
-    (813.0, Rgb{ r: 0.000004248300, g: 0.000001708950, b: 0.000000000000 }),
-    (814.0, Rgb{ r: 0.000004000200, g: 0.000001609880, b: 0.000000000000 }),
-    (815.0, Rgb{ r: 0.000003767100, g: 0.000001516770, b: 0.000000000000 }),
-    (816.0, Rgb{ r: 0.000003548000, g: 0.000001429210, b: 0.000000000000 }),
-    (817.0, Rgb{ r: 0.000003342100, g: 0.000001346860, b: 0.000000000000 }),
-    (818.0, Rgb{ r: 0.000003148500, g: 0.000001269450, b: 0.000000000000 }),
-    (819.0, Rgb{ r: 0.000002966500, g: 0.000001196620, b: 0.000000000000 }),
-    (820.0, Rgb{ r: 0.000002795310, g: 0.000001128090, b: 0.000000000000 }),
-    (821.0, Rgb{ r: 0.000002634500, g: 0.000001063680, b: 0.000000000000 }),
-    (822.0, Rgb{ r: 0.000002483400, g: 0.000001003130, b: 0.000000000000 }),
-    (823.0, Rgb{ r: 0.000002341400, g: 0.000000946220, b: 0.000000000000 }),
-    (824.0, Rgb{ r: 0.000002207800, g: 0.000000892630, b: 0.000000000000 }),
-    (825.0, Rgb{ r: 0.000002082000, g: 0.000000842160, b: 0.000000000000 }),
-    (826.0, Rgb{ r: 0.000001963600, g: 0.000000794640, b: 0.000000000000 }),
-    (827.0, Rgb{ r: 0.000001851900, g: 0.000000749780, b: 0.000000000000 }),
-    (828.0, Rgb{ r: 0.000001746500, g: 0.000000707440, b: 0.000000000000 }),
-    (829.0, Rgb{ r: 0.000001647100, g: 0.000000667480, b: 0.000000000000 }),
-    (830.0, Rgb{ r: 0.000001553140, g: 0.000000629700, b: 0.000000000000 }),
+const TABLE: [(f64, Xyz); 471] = [
+    (360.0, Xyz{ x: 0.000000122200, y: 0.000000013398, z: 0.000000535027 }),
+    (361.0, Xyz{ x: 0.000000185138, y: 0.000000020294, z: 0.000000810720 }),
+    (362.0, Xyz{ x: 0.000000278830, y: 0.000000030560, z: 0.000001221200 }),
+    (363.0, Xyz{ x: 0.000000417470, y: 0.000000045740, z: 0.000001828700 }),
+    (364.0, Xyz{ x: 0.000000621330, y: 0.000000068050, z: 0.000002722200 }),
+    (365.0, Xyz{ x: 0.000000919270, y: 0.000000100650, z: 0.000004028300 }),
+    (366.0, Xyz{ x: 0.000001351980, y: 0.000000147980, z: 0.000005925700 }),
+    (367.0, Xyz{ x: 0.000001976540, y: 0.000000216270, z: 0.000008665100 }),
+    (368.0, Xyz{ x: 0.000002872500, y: 0.000000314200, z: 0.000012596000 }),
+    (369.0, Xyz{ x: 0.000004149500, y: 0.000000453700, z: 0.000018201000 }),
+    (370.0, Xyz{ x: 0.000005958600, y: 0.000000651100, z: 0.000026143700 }),
+    (371.0, Xyz{ x: 0.000008505600, y: 0.000000928800, z: 0.000037330000 }),
+    (372.0, Xyz{ x: 0.000012068600, y: 0.000001317500, z: 0.000052987000 }),
+    (373.0, Xyz{ x: 0.000017022600, y: 0.000001857200, z: 0.000074764000 }),
+    (374.0, Xyz{ x: 0.000023868000, y: 0.000002602000, z: 0.000104870000 }),
+    (375.0, Xyz{ x: 0.000033266000, y: 0.000003625000, z: 0.000146220000 }),
+    (376.0, Xyz{ x: 0.000046087000, y: 0.000005019000, z: 0.000202660000 }),
+    (377.0, Xyz{ x: 0.000063472000, y: 0.000006907000, z: 0.000279230000 }),
+    (378.0, Xyz{ x: 0.000086892000, y: 0.000009449000, z: 0.000382450000 }),
+    (379.0, Xyz{ x: 0.000118246000, y: 0.000012848000, z: 0.000520720000 }),
+    (380.0, Xyz{ x: 0.000159952000, y: 0.000017364000, z: 0.000704776000 }),
+    (381.0, Xyz{ x: 0.000215080000, y: 0.000023327000, z: 0.000948230000 }),
+    (382.0, Xyz{ x: 0.000287490000, y: 0.000031150000, z: 0.001268200000 }),
+    (383.0, Xyz{ x: 0.000381990000, y: 0.000041350000, z: 0.001686100000 }),
+    (384.0, Xyz{ x: 0.000504550000, y: 0.000054560000, z: 0.002228500000 }),
+    (385.0, Xyz{ x: 0.000662440000, y: 0.000071560000, z: 0.002927800000 }),
+    (386.0, Xyz{ x: 0.000864500000, y: 0.000093300000, z: 0.003823700000 }),
+    (387.0, Xyz{ x: 0.001121500000, y: 0.000120870000, z: 0.004964200000 }),
+    (388.0, Xyz{ x: 0.001446160000, y: 0.000155640000, z: 0.006406700000 }),
+    (389.0, Xyz{ x: 0.001853590000, y: 0.000199200000, z: 0.008219300000 }),
+    (390.0, Xyz{ x: 0.002361600000, y: 0.000253400000, z: 0.010482200000 }),
+    (391.0, Xyz{ x: 0.002990600000, y: 0.000320200000, z: 0.013289000000 }),
+    (392.0, Xyz{ x: 0.003764500000, y: 0.000402400000, z: 0.016747000000 }),
+    (393.0, Xyz{ x: 0.004710200000, y: 0.000502300000, z: 0.020980000000 }),
+    (394.0, Xyz{ x: 0.005858100000, y: 0.000623200000, z: 0.026127000000 }),
+    (395.0, Xyz{ x: 0.007242300000, y: 0.000768500000, z: 0.032344000000 }),
+    (396.0, Xyz{ x: 0.008899600000, y: 0.000941700000, z: 0.039802000000 }),
+    (397.0, Xyz{ x: 0.010870900000, y: 0.001147800000, z: 0.048691000000 }),
+    (398.0, Xyz{ x: 0.013198900000, y: 0.001390300000, z: 0.059210000000 }),
+    (399.0, Xyz{ x: 0.015929200000, y: 0.001674000000, z: 0.071576000000 }),
+    (400.0, Xyz{ x: 0.019109700000, y: 0.002004400000, z: 0.086010900000 }),
+    (401.0, Xyz{ x: 0.022788000000, y: 0.002386000000, z: 0.102740000000 }),
+    (402.0, Xyz{ x: 0.027011000000, y: 0.002822000000, z: 0.122000000000 }),
+    (403.0, Xyz{ x: 0.031829000000, y: 0.003319000000, z: 0.144020000000 }),
+    (404.0, Xyz{ x: 0.037278000000, y: 0.003880000000, z: 0.168990000000 }),
+    (405.0, Xyz{ x: 0.043400000000, y: 0.004509000000, z: 0.197120000000 }),
+    (406.0, Xyz{ x: 0.050223000000, y: 0.005209000000, z: 0.228570000000 }),
+    (407.0, Xyz{ x: 0.057764000000, y: 0.005985000000, z: 0.263470000000 }),
+    (408.0, Xyz{ x: 0.066038000000, y: 0.006833000000, z: 0.301900000000 }),
+    (409.0, Xyz{ x: 0.075033000000, y: 0.007757000000, z: 0.343870000000 }),
+    (410.0, Xyz{ x: 0.084736000000, y: 0.008756000000, z: 0.389366000000 }),
+    (411.0, Xyz{ x: 0.095041000000, y: 0.009816000000, z: 0.437970000000 }),
+    (412.0, Xyz{ x: 0.105836000000, y: 0.010918000000, z: 0.489220000000 }),
+    (413.0, Xyz{ x: 0.117066000000, y: 0.012058000000, z: 0.542900000000 }),
+    (414.0, Xyz{ x: 0.128682000000, y: 0.013237000000, z: 0.598810000000 }),
+    (415.0, Xyz{ x: 0.140638000000, y: 0.014456000000, z: 0.656760000000 }),
+    (416.0, Xyz{ x: 0.152893000000, y: 0.015717000000, z: 0.716580000000 }),
+    (417.0, Xyz{ x: 0.165416000000, y: 0.017025000000, z: 0.778120000000 }),
+    (418.0, Xyz{ x: 0.178191000000, y: 0.018399000000, z: 0.841310000000 }),
+    (419.0, Xyz{ x: 0.191214000000, y: 0.019848000000, z: 0.906110000000 }),
+    (420.0, Xyz{ x: 0.204492000000, y: 0.021391000000, z: 0.972542000000 }),
+    (421.0, Xyz{ x: 0.217650000000, y: 0.022992000000, z: 1.038900000000 }),
+    (422.0, Xyz{ x: 0.230267000000, y: 0.024598000000, z: 1.103100000000 }),
+    (423.0, Xyz{ x: 0.242311000000, y: 0.026213000000, z: 1.165100000000 }),
+    (424.0, Xyz{ x: 0.253793000000, y: 0.027841000000, z: 1.224900000000 }),
+    (425.0, Xyz{ x: 0.264737000000, y: 0.029497000000, z: 1.282500000000 }),
+    (426.0, Xyz{ x: 0.275195000000, y: 0.031195000000, z: 1.338200000000 }),
+    (427.0, Xyz{ x: 0.285301000000, y: 0.032927000000, z: 1.392600000000 }),
+    (428.0, Xyz{ x: 0.295143000000, y: 0.034738000000, z: 1.446100000000 }),
+    (429.0, Xyz{ x: 0.304869000000, y: 0.036654000000, z: 1.499400000000 }),
+    (430.0, Xyz{ x: 0.314679000000, y: 0.038676000000, z: 1.553480000000 }),
+    (431.0, Xyz{ x: 0.324355000000, y: 0.040792000000, z: 1.607200000000 }),
+    (432.0, Xyz{ x: 0.333570000000, y: 0.042946000000, z: 1.658900000000 }),
+    (433.0, Xyz{ x: 0.342243000000, y: 0.045114000000, z: 1.708200000000 }),
+    (434.0, Xyz{ x: 0.350312000000, y: 0.047333000000, z: 1.754800000000 }),
+    (435.0, Xyz{ x: 0.357719000000, y: 0.049602000000, z: 1.798500000000 }),
+    (436.0, Xyz{ x: 0.364482000000, y: 0.051934000000, z: 1.839200000000 }),
+    (437.0, Xyz{ x: 0.370493000000, y: 0.054337000000, z: 1.876600000000 }),
+    (438.0, Xyz{ x: 0.375727000000, y: 0.056822000000, z: 1.910500000000 }),
+    (439.0, Xyz{ x: 0.380158000000, y: 0.059399000000, z: 1.940800000000 }),
+    (440.0, Xyz{ x: 0.383734000000, y: 0.062077000000, z: 1.967280000000 }),
+    (441.0, Xyz{ x: 0.386327000000, y: 0.064737000000, z: 1.989100000000 }),
+    (442.0, Xyz{ x: 0.387858000000, y: 0.067285000000, z: 2.005700000000 }),
+    (443.0, Xyz{ x: 0.388396000000, y: 0.069764000000, z: 2.017400000000 }),
+    (444.0, Xyz{ x: 0.387978000000, y: 0.072218000000, z: 2.024400000000 }),
+    (445.0, Xyz{ x: 0.386726000000, y: 0.074704000000, z: 2.027300000000 }),
+    (446.0, Xyz{ x: 0.384696000000, y: 0.077272000000, z: 2.026400000000 }),
+    (447.0, Xyz{ x: 0.382006000000, y: 0.079979000000, z: 2.022300000000 }),
+    (448.0, Xyz{ x: 0.378709000000, y: 0.082874000000, z: 2.015300000000 }),
+    (449.0, Xyz{ x: 0.374915000000, y: 0.086000000000, z: 2.006000000000 }),
+    (450.0, Xyz{ x: 0.370702000000, y: 0.089456000000, z: 1.994800000000 }),
+    (451.0, Xyz{ x: 0.366089000000, y: 0.092947000000, z: 1.981400000000 }),
+    (452.0, Xyz{ x: 0.361045000000, y: 0.096275000000, z: 1.965300000000 }),
+    (453.0, Xyz{ x: 0.355518000000, y: 0.099535000000, z: 1.946400000000 }),
+    (454.0, Xyz{ x: 0.349486000000, y: 0.102829000000, z: 1.924800000000 }),
+    (455.0, Xyz{ x: 0.342957000000, y: 0.106256000000, z: 1.900700000000 }),
+    (456.0, Xyz{ x: 0.335893000000, y: 0.109901000000, z: 1.874100000000 }),
+    (457.0, Xyz{ x: 0.328284000000, y: 0.113835000000, z: 1.845100000000 }),
+    (458.0, Xyz{ x: 0.320150000000, y: 0.118167000000, z: 1.813900000000 }),
+    (459.0, Xyz{ x: 0.311475000000, y: 0.122932000000, z: 1.780600000000 }),
+    (460.0, Xyz{ x: 0.302273000000, y: 0.128201000000, z: 1.745370000000 }),
+    (461.0, Xyz{ x: 0.292858000000, y: 0.133457000000, z: 1.709100000000 }),
+    (462.0, Xyz{ x: 0.283502000000, y: 0.138323000000, z: 1.672300000000 }),
+    (463.0, Xyz{ x: 0.274044000000, y: 0.143042000000, z: 1.634700000000 }),
+    (464.0, Xyz{ x: 0.264263000000, y: 0.147787000000, z: 1.595600000000 }),
+    (465.0, Xyz{ x: 0.254085000000, y: 0.152761000000, z: 1.554900000000 }),
+    (466.0, Xyz{ x: 0.243392000000, y: 0.158102000000, z: 1.512200000000 }),
+    (467.0, Xyz{ x: 0.232187000000, y: 0.163941000000, z: 1.467300000000 }),
+    (468.0, Xyz{ x: 0.220488000000, y: 0.170362000000, z: 1.419900000000 }),
+    (469.0, Xyz{ x: 0.208198000000, y: 0.177425000000, z: 1.370000000000 }),
+    (470.0, Xyz{ x: 0.195618000000, y: 0.185190000000, z: 1.317560000000 }),
+    (471.0, Xyz{ x: 0.183034000000, y: 0.193025000000, z: 1.262400000000 }),
+    (472.0, Xyz{ x: 0.170222000000, y: 0.200313000000, z: 1.205000000000 }),
+    (473.0, Xyz{ x: 0.157348000000, y: 0.207156000000, z: 1.146600000000 }),
+    (474.0, Xyz{ x: 0.144650000000, y: 0.213644000000, z: 1.088000000000 }),
+    (475.0, Xyz{ x: 0.132349000000, y: 0.219940000000, z: 1.030200000000 }),
+    (476.0, Xyz{ x: 0.120584000000, y: 0.226170000000, z: 0.973830000000 }),
+    (477.0, Xyz{ x: 0.109456000000, y: 0.232467000000, z: 0.919430000000 }),
+    (478.0, Xyz{ x: 0.099042000000, y: 0.239025000000, z: 0.867460000000 }),
+    (479.0, Xyz{ x: 0.089388000000, y: 0.245997000000, z: 0.818280000000 }),
+    (480.0, Xyz{ x: 0.080507000000, y: 0.253589000000, z: 0.772125000000 }),
+    (481.0, Xyz{ x: 0.072034000000, y: 0.261876000000, z: 0.728290000000 }),
+    (482.0, Xyz{ x: 0.063710000000, y: 0.270643000000, z: 0.686040000000 }),
+    (483.0, Xyz{ x: 0.055694000000, y: 0.279645000000, z: 0.645530000000 }),
+    (484.0, Xyz{ x: 0.048117000000, y: 0.288694000000, z: 0.606850000000 }),
+    (485.0, Xyz{ x: 0.041072000000, y: 0.297665000000, z: 0.570060000000 }),
+    (486.0, Xyz{ x: 0.034642000000, y: 0.306469000000, z: 0.535220000000 }),
+    (487.0, Xyz{ x: 0.028896000000, y: 0.315035000000, z: 0.502340000000 }),
+    (488.0, Xyz{ x: 0.023876000000, y: 0.323335000000, z: 0.471400000000 }),
+    (489.0, Xyz{ x: 0.019628000000, y: 0.331366000000, z: 0.442390000000 }),
+    (490.0, Xyz{ x: 0.016172000000, y: 0.339133000000, z: 0.415254000000 }),
+    (491.0, Xyz{ x: 0.013300000000, y: 0.347860000000, z: 0.390024000000 }),
+    (492.0, Xyz{ x: 0.010759000000, y: 0.358326000000, z: 0.366399000000 }),
+    (493.0, Xyz{ x: 0.008542000000, y: 0.370001000000, z: 0.344015000000 }),
+    (494.0, Xyz{ x: 0.006661000000, y: 0.382464000000, z: 0.322689000000 }),
+    (495.0, Xyz{ x: 0.005132000000, y: 0.395379000000, z: 0.302356000000 }),
+    (496.0, Xyz{ x: 0.003982000000, y: 0.408482000000, z: 0.283036000000 }),
+    (497.0, Xyz{ x: 0.003239000000, y: 0.421588000000, z: 0.264816000000 }),
+    (498.0, Xyz{ x: 0.002934000000, y: 0.434619000000, z: 0.247848000000 }),
+    (499.0, Xyz{ x: 0.003114000000, y: 0.447601000000, z: 0.232318000000 }),
+    (500.0, Xyz{ x: 0.003816000000, y: 0.460777000000, z: 0.218502000000 }),
+    (501.0, Xyz{ x: 0.005095000000, y: 0.474340000000, z: 0.205851000000 }),
+    (502.0, Xyz{ x: 0.006936000000, y: 0.488200000000, z: 0.193596000000 }),
+    (503.0, Xyz{ x: 0.009299000000, y: 0.502340000000, z: 0.181736000000 }),
+    (504.0, Xyz{ x: 0.012147000000, y: 0.516740000000, z: 0.170281000000 }),
+    (505.0, Xyz{ x: 0.015444000000, y: 0.531360000000, z: 0.159249000000 }),
+    (506.0, Xyz{ x: 0.019156000000, y: 0.546190000000, z: 0.148673000000 }),
+    (507.0, Xyz{ x: 0.023250000000, y: 0.561180000000, z: 0.138609000000 }),
+    (508.0, Xyz{ x: 0.027690000000, y: 0.576290000000, z: 0.129096000000 }),
+    (509.0, Xyz{ x: 0.032444000000, y: 0.591500000000, z: 0.120215000000 }),
+    (510.0, Xyz{ x: 0.037465000000, y: 0.606741000000, z: 0.112044000000 }),
+    (511.0, Xyz{ x: 0.042956000000, y: 0.622150000000, z: 0.104710000000 }),
+    (512.0, Xyz{ x: 0.049114000000, y: 0.637830000000, z: 0.098196000000 }),
+    (513.0, Xyz{ x: 0.055920000000, y: 0.653710000000, z: 0.092361000000 }),
+    (514.0, Xyz{ x: 0.063349000000, y: 0.669680000000, z: 0.087088000000 }),
+    (515.0, Xyz{ x: 0.071358000000, y: 0.685660000000, z: 0.082248000000 }),
+    (516.0, Xyz{ x: 0.079901000000, y: 0.701550000000, z: 0.077744000000 }),
+    (517.0, Xyz{ x: 0.088909000000, y: 0.717230000000, z: 0.073456000000 }),
+    (518.0, Xyz{ x: 0.098293000000, y: 0.732570000000, z: 0.069268000000 }),
+    (519.0, Xyz{ x: 0.107949000000, y: 0.747460000000, z: 0.065060000000 }),
+    (520.0, Xyz{ x: 0.117749000000, y: 0.761757000000, z: 0.060709000000 }),
+    (521.0, Xyz{ x: 0.127839000000, y: 0.775340000000, z: 0.056457000000 }),
+    (522.0, Xyz{ x: 0.138450000000, y: 0.788220000000, z: 0.052609000000 }),
+    (523.0, Xyz{ x: 0.149516000000, y: 0.800460000000, z: 0.049122000000 }),
+    (524.0, Xyz{ x: 0.161041000000, y: 0.812140000000, z: 0.045954000000 }),
+    (525.0, Xyz{ x: 0.172953000000, y: 0.823330000000, z: 0.043050000000 }),
+    (526.0, Xyz{ x: 0.185209000000, y: 0.834120000000, z: 0.040368000000 }),
+    (527.0, Xyz{ x: 0.197755000000, y: 0.844600000000, z: 0.037839000000 }),
+    (528.0, Xyz{ x: 0.210538000000, y: 0.854870000000, z: 0.035384000000 }),
+    (529.0, Xyz{ x: 0.223460000000, y: 0.865040000000, z: 0.032949000000 }),
+    (530.0, Xyz{ x: 0.236491000000, y: 0.875211000000, z: 0.030451000000 }),
+    (531.0, Xyz{ x: 0.249633000000, y: 0.885370000000, z: 0.028029000000 }),
+    (532.0, Xyz{ x: 0.262972000000, y: 0.895370000000, z: 0.025862000000 }),
+    (533.0, Xyz{ x: 0.276515000000, y: 0.905150000000, z: 0.023920000000 }),
+    (534.0, Xyz{ x: 0.290269000000, y: 0.914650000000, z: 0.022174000000 }),
+    (535.0, Xyz{ x: 0.304213000000, y: 0.923810000000, z: 0.020584000000 }),
+    (536.0, Xyz{ x: 0.318361000000, y: 0.932550000000, z: 0.019127000000 }),
+    (537.0, Xyz{ x: 0.332705000000, y: 0.940810000000, z: 0.017740000000 }),
+    (538.0, Xyz{ x: 0.347232000000, y: 0.948520000000, z: 0.016403000000 }),
+    (539.0, Xyz{ x: 0.361926000000, y: 0.955600000000, z: 0.015064000000 }),
+    (540.0, Xyz{ x: 0.376772000000, y: 0.961988000000, z: 0.013676000000 }),
+    (541.0, Xyz{ x: 0.391683000000, y: 0.967540000000, z: 0.012308000000 }),
+    (542.0, Xyz{ x: 0.406594000000, y: 0.972230000000, z: 0.011056000000 }),
+    (543.0, Xyz{ x: 0.421539000000, y: 0.976170000000, z: 0.009915000000 }),
+    (544.0, Xyz{ x: 0.436517000000, y: 0.979460000000, z: 0.008872000000 }),
+    (545.0, Xyz{ x: 0.451584000000, y: 0.982200000000, z: 0.007918000000 }),
+    (546.0, Xyz{ x: 0.466782000000, y: 0.984520000000, z: 0.007030000000 }),
+    (547.0, Xyz{ x: 0.482147000000, y: 0.986520000000, z: 0.006223000000 }),
+    (548.0, Xyz{ x: 0.497738000000, y: 0.988320000000, z: 0.005453000000 }),
+    (549.0, Xyz{ x: 0.513606000000, y: 0.990020000000, z: 0.004714000000 }),
+    (550.0, Xyz{ x: 0.529826000000, y: 0.991761000000, z: 0.003988000000 }),
+    (551.0, Xyz{ x: 0.546440000000, y: 0.993530000000, z: 0.003289000000 }),
+    (552.0, Xyz{ x: 0.563426000000, y: 0.995230000000, z: 0.002646000000 }),
+    (553.0, Xyz{ x: 0.580726000000, y: 0.996770000000, z: 0.002063000000 }),
+    (554.0, Xyz{ x: 0.598290000000, y: 0.998090000000, z: 0.001533000000 }),
+    (555.0, Xyz{ x: 0.616053000000, y: 0.999110000000, z: 0.001091000000 }),
+    (556.0, Xyz{ x: 0.633948000000, y: 0.999770000000, z: 0.000711000000 }),
+    (557.0, Xyz{ x: 0.651901000000, y: 1.000000000000, z: 0.000407000000 }),
+    (558.0, Xyz{ x: 0.669824000000, y: 0.999710000000, z: 0.000184000000 }),
+    (559.0, Xyz{ x: 0.687632000000, y: 0.998850000000, z: 0.000047000000 }),
+    (560.0, Xyz{ x: 0.705224000000, y: 0.997340000000, z: 0.000000000000 }),
+    (561.0, Xyz{ x: 0.722773000000, y: 0.995260000000, z: 0.000000000000 }),
+    (562.0, Xyz{ x: 0.740483000000, y: 0.992740000000, z: 0.000000000000 }),
+    (563.0, Xyz{ x: 0.758273000000, y: 0.989750000000, z: 0.000000000000 }),
+    (564.0, Xyz{ x: 0.776083000000, y: 0.986300000000, z: 0.000000000000 }),
+    (565.0, Xyz{ x: 0.793832000000, y: 0.982380000000, z: 0.000000000000 }),
+    (566.0, Xyz{ x: 0.811436000000, y: 0.977980000000, z: 0.000000000000 }),
+    (567.0, Xyz{ x: 0.828822000000, y: 0.973110000000, z: 0.000000000000 }),
+    (568.0, Xyz{ x: 0.845879000000, y: 0.967740000000, z: 0.000000000000 }),
+    (569.0, Xyz{ x: 0.862525000000, y: 0.961890000000, z: 0.000000000000 }),
+    (570.0, Xyz{ x: 0.878655000000, y: 0.955552000000, z: 0.000000000000 }),
+    (571.0, Xyz{ x: 0.894208000000, y: 0.948601000000, z: 0.000000000000 }),
+    (572.0, Xyz{ x: 0.909206000000, y: 0.940981000000, z: 0.000000000000 }),
+    (573.0, Xyz{ x: 0.923672000000, y: 0.932798000000, z: 0.000000000000 }),
+    (574.0, Xyz{ x: 0.937638000000, y: 0.924158000000, z: 0.000000000000 }),
+    (575.0, Xyz{ x: 0.951162000000, y: 0.915175000000, z: 0.000000000000 }),
+    (576.0, Xyz{ x: 0.964283000000, y: 0.905954000000, z: 0.000000000000 }),
+    (577.0, Xyz{ x: 0.977068000000, y: 0.896608000000, z: 0.000000000000 }),
+    (578.0, Xyz{ x: 0.989590000000, y: 0.887249000000, z: 0.000000000000 }),
+    (579.0, Xyz{ x: 1.001910000000, y: 0.877986000000, z: 0.000000000000 }),
+    (580.0, Xyz{ x: 1.014160000000, y: 0.868934000000, z: 0.000000000000 }),
+    (581.0, Xyz{ x: 1.026500000000, y: 0.860164000000, z: 0.000000000000 }),
+    (582.0, Xyz{ x: 1.038800000000, y: 0.851519000000, z: 0.000000000000 }),
+    (583.0, Xyz{ x: 1.051000000000, y: 0.842963000000, z: 0.000000000000 }),
+    (584.0, Xyz{ x: 1.062900000000, y: 0.834393000000, z: 0.000000000000 }),
+    (585.0, Xyz{ x: 1.074300000000, y: 0.825623000000, z: 0.000000000000 }),
+    (586.0, Xyz{ x: 1.085200000000, y: 0.816764000000, z: 0.000000000000 }),
+    (587.0, Xyz{ x: 1.095200000000, y: 0.807544000000, z: 0.000000000000 }),
+    (588.0, Xyz{ x: 1.104200000000, y: 0.797947000000, z: 0.000000000000 }),
+    (589.0, Xyz{ x: 1.112000000000, y: 0.787893000000, z: 0.000000000000 }),
+    (590.0, Xyz{ x: 1.118520000000, y: 0.777405000000, z: 0.000000000000 }),
+    (591.0, Xyz{ x: 1.123800000000, y: 0.766490000000, z: 0.000000000000 }),
+    (592.0, Xyz{ x: 1.128000000000, y: 0.755309000000, z: 0.000000000000 }),
+    (593.0, Xyz{ x: 1.131100000000, y: 0.743845000000, z: 0.000000000000 }),
+    (594.0, Xyz{ x: 1.133200000000, y: 0.732190000000, z: 0.000000000000 }),
+    (595.0, Xyz{ x: 1.134300000000, y: 0.720353000000, z: 0.000000000000 }),
+    (596.0, Xyz{ x: 1.134300000000, y: 0.708281000000, z: 0.000000000000 }),
+    (597.0, Xyz{ x: 1.133300000000, y: 0.696055000000, z: 0.000000000000 }),
+    (598.0, Xyz{ x: 1.131200000000, y: 0.683621000000, z: 0.000000000000 }),
+    (599.0, Xyz{ x: 1.128100000000, y: 0.671048000000, z: 0.000000000000 }),
+    (600.0, Xyz{ x: 1.123990000000, y: 0.658341000000, z: 0.000000000000 }),
+    (601.0, Xyz{ x: 1.118900000000, y: 0.645545000000, z: 0.000000000000 }),
+    (602.0, Xyz{ x: 1.112900000000, y: 0.632718000000, z: 0.000000000000 }),
+    (603.0, Xyz{ x: 1.105900000000, y: 0.619815000000, z: 0.000000000000 }),
+    (604.0, Xyz{ x: 1.098000000000, y: 0.606887000000, z: 0.000000000000 }),
+    (605.0, Xyz{ x: 1.089100000000, y: 0.593878000000, z: 0.000000000000 }),
+    (606.0, Xyz{ x: 1.079200000000, y: 0.580781000000, z: 0.000000000000 }),
+    (607.0, Xyz{ x: 1.068400000000, y: 0.567653000000, z: 0.000000000000 }),
+    (608.0, Xyz{ x: 1.056700000000, y: 0.554490000000, z: 0.000000000000 }),
+    (609.0, Xyz{ x: 1.044000000000, y: 0.541228000000, z: 0.000000000000 }),
+    (610.0, Xyz{ x: 1.030480000000, y: 0.527963000000, z: 0.000000000000 }),
+    (611.0, Xyz{ x: 1.016000000000, y: 0.514634000000, z: 0.000000000000 }),
+    (612.0, Xyz{ x: 1.000800000000, y: 0.501363000000, z: 0.000000000000 }),
+    (613.0, Xyz{ x: 0.984790000000, y: 0.488124000000, z: 0.000000000000 }),
+    (614.0, Xyz{ x: 0.968080000000, y: 0.474935000000, z: 0.000000000000 }),
+    (615.0, Xyz{ x: 0.950740000000, y: 0.461834000000, z: 0.000000000000 }),
+    (616.0, Xyz{ x: 0.932800000000, y: 0.448823000000, z: 0.000000000000 }),
+    (617.0, Xyz{ x: 0.914340000000, y: 0.435917000000, z: 0.000000000000 }),
+    (618.0, Xyz{ x: 0.895390000000, y: 0.423153000000, z: 0.000000000000 }),
+    (619.0, Xyz{ x: 0.876030000000, y: 0.410526000000, z: 0.000000000000 }),
+    (620.0, Xyz{ x: 0.856297000000, y: 0.398057000000, z: 0.000000000000 }),
+    (621.0, Xyz{ x: 0.836350000000, y: 0.385835000000, z: 0.000000000000 }),
+    (622.0, Xyz{ x: 0.816290000000, y: 0.373951000000, z: 0.000000000000 }),
+    (623.0, Xyz{ x: 0.796050000000, y: 0.362311000000, z: 0.000000000000 }),
+    (624.0, Xyz{ x: 0.775610000000, y: 0.350863000000, z: 0.000000000000 }),
+    (625.0, Xyz{ x: 0.754930000000, y: 0.339554000000, z: 0.000000000000 }),
+    (626.0, Xyz{ x: 0.733990000000, y: 0.328309000000, z: 0.000000000000 }),
+    (627.0, Xyz{ x: 0.712780000000, y: 0.317118000000, z: 0.000000000000 }),
+    (628.0, Xyz{ x: 0.691290000000, y: 0.305936000000, z: 0.000000000000 }),
+    (629.0, Xyz{ x: 0.669520000000, y: 0.294737000000, z: 0.000000000000 }),
+    (630.0, Xyz{ x: 0.647467000000, y: 0.283493000000, z: 0.000000000000 }),
+    (631.0, Xyz{ x: 0.625110000000, y: 0.272222000000, z: 0.000000000000 }),
+    (632.0, Xyz{ x: 0.602520000000, y: 0.260990000000, z: 0.000000000000 }),
+    (633.0, Xyz{ x: 0.579890000000, y: 0.249877000000, z: 0.000000000000 }),
+    (634.0, Xyz{ x: 0.557370000000, y: 0.238946000000, z: 0.000000000000 }),
+    (635.0, Xyz{ x: 0.535110000000, y: 0.228254000000, z: 0.000000000000 }),
+    (636.0, Xyz{ x: 0.513240000000, y: 0.217853000000, z: 0.000000000000 }),
+    (637.0, Xyz{ x: 0.491860000000, y: 0.207780000000, z: 0.000000000000 }),
+    (638.0, Xyz{ x: 0.471080000000, y: 0.198072000000, z: 0.000000000000 }),
+    (639.0, Xyz{ x: 0.450960000000, y: 0.188748000000, z: 0.000000000000 }),
+    (640.0, Xyz{ x: 0.431567000000, y: 0.179828000000, z: 0.000000000000 }),
+    (641.0, Xyz{ x: 0.412870000000, y: 0.171285000000, z: 0.000000000000 }),
+    (642.0, Xyz{ x: 0.394750000000, y: 0.163059000000, z: 0.000000000000 }),
+    (643.0, Xyz{ x: 0.377210000000, y: 0.155151000000, z: 0.000000000000 }),
+    (644.0, Xyz{ x: 0.360190000000, y: 0.147535000000, z: 0.000000000000 }),
+    (645.0, Xyz{ x: 0.343690000000, y: 0.140211000000, z: 0.000000000000 }),
+    (646.0, Xyz{ x: 0.327690000000, y: 0.133170000000, z: 0.000000000000 }),
+    (647.0, Xyz{ x: 0.312170000000, y: 0.126400000000, z: 0.000000000000 }),
+    (648.0, Xyz{ x: 0.297110000000, y: 0.119892000000, z: 0.000000000000 }),
+    (649.0, Xyz{ x: 0.282500000000, y: 0.113640000000, z: 0.000000000000 }),
+    (650.0, Xyz{ x: 0.268329000000, y: 0.107633000000, z: 0.000000000000 }),
+    (651.0, Xyz{ x: 0.254590000000, y: 0.101870000000, z: 0.000000000000 }),
+    (652.0, Xyz{ x: 0.241300000000, y: 0.096347000000, z: 0.000000000000 }),
+    (653.0, Xyz{ x: 0.228480000000, y: 0.091063000000, z: 0.000000000000 }),
+    (654.0, Xyz{ x: 0.216140000000, y: 0.086010000000, z: 0.000000000000 }),
+    (655.0, Xyz{ x: 0.204300000000, y: 0.081187000000, z: 0.000000000000 }),
+    (656.0, Xyz{ x: 0.192950000000, y: 0.076583000000, z: 0.000000000000 }),
+    (657.0, Xyz{ x: 0.182110000000, y: 0.072198000000, z: 0.000000000000 }),
+    (658.0, Xyz{ x: 0.171770000000, y: 0.068024000000, z: 0.000000000000 }),
+    (659.0, Xyz{ x: 0.161920000000, y: 0.064052000000, z: 0.000000000000 }),
+    (660.0, Xyz{ x: 0.152568000000, y: 0.060281000000, z: 0.000000000000 }),
+    (661.0, Xyz{ x: 0.143670000000, y: 0.056697000000, z: 0.000000000000 }),
+    (662.0, Xyz{ x: 0.135200000000, y: 0.053292000000, z: 0.000000000000 }),
+    (663.0, Xyz{ x: 0.127130000000, y: 0.050059000000, z: 0.000000000000 }),
+    (664.0, Xyz{ x: 0.119480000000, y: 0.046998000000, z: 0.000000000000 }),
+    (665.0, Xyz{ x: 0.112210000000, y: 0.044096000000, z: 0.000000000000 }),
+    (666.0, Xyz{ x: 0.105310000000, y: 0.041345000000, z: 0.000000000000 }),
+    (667.0, Xyz{ x: 0.098786000000, y: 0.038750700000, z: 0.000000000000 }),
+    (668.0, Xyz{ x: 0.092610000000, y: 0.036297800000, z: 0.000000000000 }),
+    (669.0, Xyz{ x: 0.086773000000, y: 0.033983200000, z: 0.000000000000 }),
+    (670.0, Xyz{ x: 0.081260600000, y: 0.031800400000, z: 0.000000000000 }),
+    (671.0, Xyz{ x: 0.076048000000, y: 0.029739500000, z: 0.000000000000 }),
+    (672.0, Xyz{ x: 0.071114000000, y: 0.027791800000, z: 0.000000000000 }),
+    (673.0, Xyz{ x: 0.066454000000, y: 0.025955100000, z: 0.000000000000 }),
+    (674.0, Xyz{ x: 0.062062000000, y: 0.024226300000, z: 0.000000000000 }),
+    (675.0, Xyz{ x: 0.057930000000, y: 0.022601700000, z: 0.000000000000 }),
+    (676.0, Xyz{ x: 0.054050000000, y: 0.021077900000, z: 0.000000000000 }),
+    (677.0, Xyz{ x: 0.050412000000, y: 0.019650500000, z: 0.000000000000 }),
+    (678.0, Xyz{ x: 0.047006000000, y: 0.018315300000, z: 0.000000000000 }),
+    (679.0, Xyz{ x: 0.043823000000, y: 0.017068600000, z: 0.000000000000 }),
+    (680.0, Xyz{ x: 0.040850800000, y: 0.015905100000, z: 0.000000000000 }),
+    (681.0, Xyz{ x: 0.038072000000, y: 0.014818300000, z: 0.000000000000 }),
+    (682.0, Xyz{ x: 0.035468000000, y: 0.013800800000, z: 0.000000000000 }),
+    (683.0, Xyz{ x: 0.033031000000, y: 0.012849500000, z: 0.000000000000 }),
+    (684.0, Xyz{ x: 0.030753000000, y: 0.011960700000, z: 0.000000000000 }),
+    (685.0, Xyz{ x: 0.028623000000, y: 0.011130300000, z: 0.000000000000 }),
+    (686.0, Xyz{ x: 0.026635000000, y: 0.010355500000, z: 0.000000000000 }),
+    (687.0, Xyz{ x: 0.024781000000, y: 0.009633200000, z: 0.000000000000 }),
+    (688.0, Xyz{ x: 0.023052000000, y: 0.008959900000, z: 0.000000000000 }),
+    (689.0, Xyz{ x: 0.021441000000, y: 0.008332400000, z: 0.000000000000 }),
+    (690.0, Xyz{ x: 0.019941300000, y: 0.007748800000, z: 0.000000000000 }),
+    (691.0, Xyz{ x: 0.018544000000, y: 0.007204600000, z: 0.000000000000 }),
+    (692.0, Xyz{ x: 0.017241000000, y: 0.006697500000, z: 0.000000000000 }),
+    (693.0, Xyz{ x: 0.016027000000, y: 0.006225100000, z: 0.000000000000 }),
+    (694.0, Xyz{ x: 0.014896000000, y: 0.005785000000, z: 0.000000000000 }),
+    (695.0, Xyz{ x: 0.013842000000, y: 0.005375100000, z: 0.000000000000 }),
+    (696.0, Xyz{ x: 0.012862000000, y: 0.004994100000, z: 0.000000000000 }),
+    (697.0, Xyz{ x: 0.011949000000, y: 0.004639200000, z: 0.000000000000 }),
+    (698.0, Xyz{ x: 0.011100000000, y: 0.004309300000, z: 0.000000000000 }),
+    (699.0, Xyz{ x: 0.010311000000, y: 0.004002800000, z: 0.000000000000 }),
+    (700.0, Xyz{ x: 0.009576880000, y: 0.003717740000, z: 0.000000000000 }),
+    (701.0, Xyz{ x: 0.008894000000, y: 0.003452620000, z: 0.000000000000 }),
+    (702.0, Xyz{ x: 0.008258100000, y: 0.003205830000, z: 0.000000000000 }),
+    (703.0, Xyz{ x: 0.007666400000, y: 0.002976230000, z: 0.000000000000 }),
+    (704.0, Xyz{ x: 0.007116300000, y: 0.002762810000, z: 0.000000000000 }),
+    (705.0, Xyz{ x: 0.006605200000, y: 0.002564560000, z: 0.000000000000 }),
+    (706.0, Xyz{ x: 0.006130600000, y: 0.002380480000, z: 0.000000000000 }),
+    (707.0, Xyz{ x: 0.005690300000, y: 0.002209710000, z: 0.000000000000 }),
+    (708.0, Xyz{ x: 0.005281900000, y: 0.002051320000, z: 0.000000000000 }),
+    (709.0, Xyz{ x: 0.004903300000, y: 0.001904490000, z: 0.000000000000 }),
+    (710.0, Xyz{ x: 0.004552630000, y: 0.001768470000, z: 0.000000000000 }),
+    (711.0, Xyz{ x: 0.004227500000, y: 0.001642360000, z: 0.000000000000 }),
+    (712.0, Xyz{ x: 0.003925800000, y: 0.001525350000, z: 0.000000000000 }),
+    (713.0, Xyz{ x: 0.003645700000, y: 0.001416720000, z: 0.000000000000 }),
+    (714.0, Xyz{ x: 0.003385900000, y: 0.001315950000, z: 0.000000000000 }),
+    (715.0, Xyz{ x: 0.003144700000, y: 0.001222390000, z: 0.000000000000 }),
+    (716.0, Xyz{ x: 0.002920800000, y: 0.001135550000, z: 0.000000000000 }),
+    (717.0, Xyz{ x: 0.002713000000, y: 0.001054940000, z: 0.000000000000 }),
+    (718.0, Xyz{ x: 0.002520200000, y: 0.000980140000, z: 0.000000000000 }),
+    (719.0, Xyz{ x: 0.002341100000, y: 0.000910660000, z: 0.000000000000 }),
+    (720.0, Xyz{ x: 0.002174960000, y: 0.000846190000, z: 0.000000000000 }),
+    (721.0, Xyz{ x: 0.002020600000, y: 0.000786290000, z: 0.000000000000 }),
+    (722.0, Xyz{ x: 0.001877300000, y: 0.000730680000, z: 0.000000000000 }),
+    (723.0, Xyz{ x: 0.001744100000, y: 0.000678990000, z: 0.000000000000 }),
+    (724.0, Xyz{ x: 0.001620500000, y: 0.000631010000, z: 0.000000000000 }),
+    (725.0, Xyz{ x: 0.001505700000, y: 0.000586440000, z: 0.000000000000 }),
+    (726.0, Xyz{ x: 0.001399200000, y: 0.000545110000, z: 0.000000000000 }),
+    (727.0, Xyz{ x: 0.001300400000, y: 0.000506720000, z: 0.000000000000 }),
+    (728.0, Xyz{ x: 0.001208700000, y: 0.000471110000, z: 0.000000000000 }),
+    (729.0, Xyz{ x: 0.001123600000, y: 0.000438050000, z: 0.000000000000 }),
+    (730.0, Xyz{ x: 0.001044760000, y: 0.000407410000, z: 0.000000000000 }),
+    (731.0, Xyz{ x: 0.000971560000, y: 0.000378962000, z: 0.000000000000 }),
+    (732.0, Xyz{ x: 0.000903600000, y: 0.000352543000, z: 0.000000000000 }),
+    (733.0, Xyz{ x: 0.000840480000, y: 0.000328001000, z: 0.000000000000 }),
+    (734.0, Xyz{ x: 0.000781870000, y: 0.000305208000, z: 0.000000000000 }),
+    (735.0, Xyz{ x: 0.000727450000, y: 0.000284041000, z: 0.000000000000 }),
+    (736.0, Xyz{ x: 0.000676900000, y: 0.000264375000, z: 0.000000000000 }),
+    (737.0, Xyz{ x: 0.000629960000, y: 0.000246109000, z: 0.000000000000 }),
+    (738.0, Xyz{ x: 0.000586370000, y: 0.000229143000, z: 0.000000000000 }),
+    (739.0, Xyz{ x: 0.000545870000, y: 0.000213376000, z: 0.000000000000 }),
+    (740.0, Xyz{ x: 0.000508258000, y: 0.000198730000, z: 0.000000000000 }),
+    (741.0, Xyz{ x: 0.000473300000, y: 0.000185115000, z: 0.000000000000 }),
+    (742.0, Xyz{ x: 0.000440800000, y: 0.000172454000, z: 0.000000000000 }),
+    (743.0, Xyz{ x: 0.000410580000, y: 0.000160678000, z: 0.000000000000 }),
+    (744.0, Xyz{ x: 0.000382490000, y: 0.000149730000, z: 0.000000000000 }),
+    (745.0, Xyz{ x: 0.000356380000, y: 0.000139550000, z: 0.000000000000 }),
+    (746.0, Xyz{ x: 0.000332110000, y: 0.000130086000, z: 0.000000000000 }),
+    (747.0, Xyz{ x: 0.000309550000, y: 0.000121290000, z: 0.000000000000 }),
+    (748.0, Xyz{ x: 0.000288580000, y: 0.000113106000, z: 0.000000000000 }),
+    (749.0, Xyz{ x: 0.000269090000, y: 0.000105501000, z: 0.000000000000 }),
+    (750.0, Xyz{ x: 0.000250969000, y: 0.000098428000, z: 0.000000000000 }),
+    (751.0, Xyz{ x: 0.000234130000, y: 0.000091853000, z: 0.000000000000 }),
+    (752.0, Xyz{ x: 0.000218470000, y: 0.000085738000, z: 0.000000000000 }),
+    (753.0, Xyz{ x: 0.000203910000, y: 0.000080048000, z: 0.000000000000 }),
+    (754.0, Xyz{ x: 0.000190350000, y: 0.000074751000, z: 0.000000000000 }),
+    (755.0, Xyz{ x: 0.000177730000, y: 0.000069819000, z: 0.000000000000 }),
+    (756.0, Xyz{ x: 0.000165970000, y: 0.000065222000, z: 0.000000000000 }),
+    (757.0, Xyz{ x: 0.000155020000, y: 0.000060939000, z: 0.000000000000 }),
+    (758.0, Xyz{ x: 0.000144800000, y: 0.000056942000, z: 0.000000000000 }),
+    (759.0, Xyz{ x: 0.000135280000, y: 0.000053217000, z: 0.000000000000 }),
+    (760.0, Xyz{ x: 0.000126390000, y: 0.000049737000, z: 0.000000000000 }),
+    (761.0, Xyz{ x: 0.000118100000, y: 0.000046491000, z: 0.000000000000 }),
+    (762.0, Xyz{ x: 0.000110370000, y: 0.000043464000, z: 0.000000000000 }),
+    (763.0, Xyz{ x: 0.000103150000, y: 0.000040635000, z: 0.000000000000 }),
+    (764.0, Xyz{ x: 0.000096427000, y: 0.000038000000, z: 0.000000000000 }),
+    (765.0, Xyz{ x: 0.000090151000, y: 0.000035540500, z: 0.000000000000 }),
+    (766.0, Xyz{ x: 0.000084294000, y: 0.000033244800, z: 0.000000000000 }),
+    (767.0, Xyz{ x: 0.000078830000, y: 0.000031100600, z: 0.000000000000 }),
+    (768.0, Xyz{ x: 0.000073729000, y: 0.000029099000, z: 0.000000000000 }),
+    (769.0, Xyz{ x: 0.000068969000, y: 0.000027230700, z: 0.000000000000 }),
+    (770.0, Xyz{ x: 0.000064525800, y: 0.000025486000, z: 0.000000000000 }),
+    (771.0, Xyz{ x: 0.000060376000, y: 0.000023856100, z: 0.000000000000 }),
+    (772.0, Xyz{ x: 0.000056500000, y: 0.000022333200, z: 0.000000000000 }),
+    (773.0, Xyz{ x: 0.000052880000, y: 0.000020910400, z: 0.000000000000 }),
+    (774.0, Xyz{ x: 0.000049498000, y: 0.000019580800, z: 0.000000000000 }),
+    (775.0, Xyz{ x: 0.000046339000, y: 0.000018338400, z: 0.000000000000 }),
+    (776.0, Xyz{ x: 0.000043389000, y: 0.000017177700, z: 0.000000000000 }),
+    (777.0, Xyz{ x: 0.000040634000, y: 0.000016093400, z: 0.000000000000 }),
+    (778.0, Xyz{ x: 0.000038060000, y: 0.000015080000, z: 0.000000000000 }),
+    (779.0, Xyz{ x: 0.000035657000, y: 0.000014133600, z: 0.000000000000 }),
+    (780.0, Xyz{ x: 0.000033411700, y: 0.000013249000, z: 0.000000000000 }),
+    (781.0, Xyz{ x: 0.000031315000, y: 0.000012422600, z: 0.000000000000 }),
+    (782.0, Xyz{ x: 0.000029355000, y: 0.000011649900, z: 0.000000000000 }),
+    (783.0, Xyz{ x: 0.000027524000, y: 0.000010927700, z: 0.000000000000 }),
+    (784.0, Xyz{ x: 0.000025811000, y: 0.000010251900, z: 0.000000000000 }),
+    (785.0, Xyz{ x: 0.000024209000, y: 0.000009619600, z: 0.000000000000 }),
+    (786.0, Xyz{ x: 0.000022711000, y: 0.000009028100, z: 0.000000000000 }),
+    (787.0, Xyz{ x: 0.000021308000, y: 0.000008474000, z: 0.000000000000 }),
+    (788.0, Xyz{ x: 0.000019994000, y: 0.000007954800, z: 0.000000000000 }),
+    (789.0, Xyz{ x: 0.000018764000, y: 0.000007468600, z: 0.000000000000 }),
+    (790.0, Xyz{ x: 0.000017611500, y: 0.000007012800, z: 0.000000000000 }),
+    (791.0, Xyz{ x: 0.000016532000, y: 0.000006585800, z: 0.000000000000 }),
+    (792.0, Xyz{ x: 0.000015521000, y: 0.000006185700, z: 0.000000000000 }),
+    (793.0, Xyz{ x: 0.000014574000, y: 0.000005810700, z: 0.000000000000 }),
+    (794.0, Xyz{ x: 0.000013686000, y: 0.000005459000, z: 0.000000000000 }),
+    (795.0, Xyz{ x: 0.000012855000, y: 0.000005129800, z: 0.000000000000 }),
+    (796.0, Xyz{ x: 0.000012075000, y: 0.000004820600, z: 0.000000000000 }),
+    (797.0, Xyz{ x: 0.000011345000, y: 0.000004531200, z: 0.000000000000 }),
+    (798.0, Xyz{ x: 0.000010659000, y: 0.000004259100, z: 0.000000000000 }),
+    (799.0, Xyz{ x: 0.000010017000, y: 0.000004004200, z: 0.000000000000 }),
+    (800.0, Xyz{ x: 0.000009413630, y: 0.000003764730, z: 0.000000000000 }),
+    (801.0, Xyz{ x: 0.000008847900, y: 0.000003539950, z: 0.000000000000 }),
+    (802.0, Xyz{ x: 0.000008317100, y: 0.000003329140, z: 0.000000000000 }),
+    (803.0, Xyz{ x: 0.000007819000, y: 0.000003131150, z: 0.000000000000 }),
+    (804.0, Xyz{ x: 0.000007351600, y: 0.000002945290, z: 0.000000000000 }),
+    (805.0, Xyz{ x: 0.000006913000, y: 0.000002770810, z: 0.000000000000 }),
+    (806.0, Xyz{ x: 0.000006501500, y: 0.000002607050, z: 0.000000000000 }),
+    (807.0, Xyz{ x: 0.000006115300, y: 0.000002453290, z: 0.000000000000 }),
+    (808.0, Xyz{ x: 0.000005752900, y: 0.000002308940, z: 0.000000000000 }),
+    (809.0, Xyz{ x: 0.000005412700, y: 0.000002173380, z: 0.000000000000 }),
+    (810.0, Xyz{ x: 0.000005093470, y: 0.000002046130, z: 0.000000000000 }),
+    (811.0, Xyz{ x: 0.000004793800, y: 0.000001926620, z: 0.000000000000 }),
+    (812.0, Xyz{ x: 0.000004512500, y: 0.000001814400, z: 0.000000000000 }),
+    (813.0, Xyz{ x: 0.000004248300, y: 0.000001708950, z: 0.000000000000 }),
+    (814.0, Xyz{ x: 0.000004000200, y: 0.000001609880, z: 0.000000000000 }),
+    (815.0, Xyz{ x: 0.000003767100, y: 0.000001516770, z: 0.000000000000 }),
+    (816.0, Xyz{ x: 0.000003548000, y: 0.000001429210, z: 0.000000000000 }),
+    (817.0, Xyz{ x: 0.000003342100, y: 0.000001346860, z: 0.000000000000 }),
+    (818.0, Xyz{ x: 0.000003148500, y: 0.000001269450, z: 0.000000000000 }),
+    (819.0, Xyz{ x: 0.000002966500, y: 0.000001196620, z: 0.000000000000 }),
+    (820.0, Xyz{ x: 0.000002795310, y: 0.000001128090, z: 0.000000000000 }),
+    (821.0, Xyz{ x: 0.000002634500, y: 0.000001063680, z: 0.000000000000 }),
+    (822.0, Xyz{ x: 0.000002483400, y: 0.000001003130, z: 0.000000000000 }),
+    (823.0, Xyz{ x: 0.000002341400, y: 0.000000946220, z: 0.000000000000 }),
+    (824.0, Xyz{ x: 0.000002207800, y: 0.000000892630, z: 0.000000000000 }),
+    (825.0, Xyz{ x: 0.000002082000, y: 0.000000842160, z: 0.000000000000 }),
+    (826.0, Xyz{ x: 0.000001963600, y: 0.000000794640, z: 0.000000000000 }),
+    (827.0, Xyz{ x: 0.000001851900, y: 0.000000749780, z: 0.000000000000 }),
+    (828.0, Xyz{ x: 0.000001746500, y: 0.000000707440, z: 0.000000000000 }),
+    (829.0, Xyz{ x: 0.000001647100, y: 0.000000667480, z: 0.000000000000 }),
+    (830.0, Xyz{ x: 0.000001553140, y: 0.000000629700, z: 0.000000000000 }),
 ];
+
+#[cfg(test)]
+mod test {
+    use super::{
+        WaveLengthFactory, WaveLengthLinearFactory, WaveLengthTrimmedFactory,
+        WaveLengthUniformFactory, WaveLengthStratifiedFactory, WaveLengthImportanceFactory,
+        WaveLengthSampling,
+    };
+    use crate::core::{ColorMatching, Xyz};
+
+    /// Weighted mean of the color matching functions over many samples of the pixel.
+    fn mean<F>(factory: &F, samples: usize) -> (f64, f64, f64)
+    where
+        F: WaveLengthFactory,
+    {
+        let mut rng = rand::thread_rng();
+        let n = (samples * factory.resolution()) as f64;
+        (0..samples)
+            .flat_map(|_| factory.iter(&mut rng))
+            .map(|(l, weight)| {
+                let (x, y, z) = l.xyz().tuple();
+                (x * weight, y * weight, z * weight)
+            })
+            .fold((0.0, 0.0, 0.0), |(a, b, c), (x, y, z)| {
+                (a + x / n, b + y / n, c + z / n)
+            })
+    }
+
+    #[test]
+    fn unbiased() {
+        let cie = ColorMatching::default();
+        let (x, y, z) = mean(&WaveLengthLinearFactory::new(4701), 1);
+        let close = |(a, b, c): (f64, f64, f64), tolerance: f64| {
+            assert!((a - x).abs() < x * tolerance);
+            assert!((b - y).abs() < y * tolerance);
+            assert!((c - z).abs() < z * tolerance);
+        };
+        close(mean(&WaveLengthUniformFactory::new(16), 5000), 0.05);
+        close(mean(&WaveLengthStratifiedFactory::new(16), 500), 0.05);
+        close(mean(&WaveLengthImportanceFactory::new(16, &cie), 500), 0.02);
+        close(mean(&WaveLengthLinearFactory::new(48), 1), 0.02);
+        close(mean(&WaveLengthTrimmedFactory, 1), 0.02);
+    }
+
+    #[test]
+    fn importance() {
+        let factory = WaveLengthImportanceFactory::new(1, &ColorMatching::default());
+        let (l, density) = factory.invert(0.5);
+        assert!(l.0 > 450.0 && l.0 < 650.0);
+        assert!(density > 1.0);
+        let (l, density) = factory.invert(0.0);
+        assert_eq!(l.0, 360.0);
+        assert!(density < 1e-3);
+    }
+
+    #[test]
+    fn observer() {
+        // the sensor responding only from 500 to 600 nanometers
+        let sensor = ColorMatching::new(vec![
+            (500.0, Xyz::new(0.0, 0.0, 0.0)),
+            (550.0, Xyz::new(1.0, 1.0, 1.0)),
+            (600.0, Xyz::new(0.0, 0.0, 0.0)),
+        ])
+        .unwrap();
+        let factory = WaveLengthImportanceFactory::new(8, &sensor);
+        let mut rng = rand::thread_rng();
+        let n = 1000;
+        let mut sum = 0.0;
+        for (l, weight) in (0..n).flat_map(|_| factory.iter(&mut rng)) {
+            assert!(l.0 >= 500.0 && l.0 <= 600.0);
+            sum += sensor.xyz(&l).tuple().1 * weight;
+        }
+        // the mean response over the range of the table, 50 of 470 nanometers
+        let expected = 50.0 / 470.0;
+        let found = sum / ((n * 8) as f64);
+        assert!((found - expected).abs() < expected * 0.01);
+
+        let factory = WaveLengthImportanceFactory::new(
+            2,
+            &ColorMatching::new(vec![(1000.0, Xyz::default())]).unwrap(),
+        );
+        assert!(factory
+            .iter(&mut rng)
+            .all(|(_, weight)| (weight - 1.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn sampling() {
+        let observer = ColorMatching::default();
+        for json in [
+            r#""Trimmed""#,
+            r#"{ "Linear": 64 }"#,
+            r#"{ "Importance": 16 }"#,
+        ]
+        .iter()
+        {
+            let sampling: WaveLengthSampling = serde_json::from_str(json).unwrap();
+            let factory = sampling.factory(&observer).unwrap();
+            let identity = factory.identity();
+            let again = WaveLengthSampling::from_identity(identity.as_str()).unwrap();
+            assert_eq!(again.factory(&observer).unwrap().identity(), identity);
+        }
+
+        // the importance of the other observer differs
+        let sensor = ColorMatching::new(vec![(500.0, Xyz::new(1.0, 1.0, 1.0))]).unwrap();
+        let importance = WaveLengthSampling::Importance(16);
+        let identity = importance.factory(&sensor).unwrap().identity();
+        assert_ne!(importance.factory(&observer).unwrap().identity(), identity);
+        assert!(WaveLengthSampling::from_identity(identity.as_str()).is_some());
+        assert!(WaveLengthSampling::from_identity("importance 16").is_none());
+        assert!(WaveLengthSampling::Linear(1).factory(&observer).is_none());
+        assert!(WaveLengthSampling::Uniform(0).factory(&observer).is_none());
+        assert!(WaveLengthSampling::from_identity("trimmed").is_none());
+    }
+}
@@ -0,0 +1,124 @@
+use crate::core::{WaveLength, Spectrum};
+
+use serde::{Serialize, Deserialize};
+
+/// Complex index of refraction `n + ik` of the metal, the outer side is the vacuum.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum Conductor {
+    Gold,
+    Copper,
+    Silver,
+    Aluminum,
+    Custom { n: Spectrum, k: Spectrum },
+}
+
+// `(wave length in nanometers, n, k)`
+const GOLD: [(f64, f64, f64); 7] = [
+    (400.0, 1.658, 1.956),
+    (450.0, 1.500, 1.880),
+    (500.0, 0.970, 1.870),
+    (550.0, 0.430, 2.450),
+    (600.0, 0.250, 2.980),
+    (650.0, 0.170, 3.440),
+    (700.0, 0.160, 3.950),
+];
+
+const COPPER: [(f64, f64, f64); 7] = [
+    (400.0, 1.180, 2.210),
+    (450.0, 1.170, 2.400),
+    (500.0, 1.130, 2.560),
+    (550.0, 1.020, 2.580),
+    (600.0, 0.300, 3.210),
+    (650.0, 0.210, 3.670),
+    (700.0, 0.210, 4.050),
+];
+
+const SILVER: [(f64, f64, f64); 7] = [
+    (400.0, 0.050, 2.100),
+    (450.0, 0.040, 2.650),
+    (500.0, 0.050, 3.130),
+    (550.0, 0.060, 3.590),
+    (600.0, 0.060, 4.010),
+    (650.0, 0.060, 4.450),
+    (700.0, 0.040, 4.840),
+];
+
+const ALUMINUM: [(f64, f64, f64); 7] = [
+    (400.0, 0.490, 4.860),
+    (450.0, 0.620, 5.470),
+    (500.0, 0.770, 6.080),
+    (550.0, 0.960, 6.690),
+    (600.0, 1.200, 7.260),
+    (650.0, 1.470, 7.790),
+    (700.0, 1.830, 8.310),
+];
+
+/// Linear between the rows and constant beyond the ends, like the `Spectrum`.
+fn interpolate(table: &[(f64, f64, f64)], l: f64) -> (f64, f64) {
+    let i = table.partition_point(|&(x, _, _)| x < l);
+    if i == 0 {
+        (table[0].1, table[0].2)
+    } else if i == table.len() {
+        (table[i - 1].1, table[i - 1].2)
+    } else {
+        let (x0, n0, k0) = table[i - 1];
+        let (x1, n1, k1) = table[i];
+        let s = (l - x0) / (x1 - x0);
+        (n0 + (n1 - n0) * s, k0 + (k1 - k0) * s)
+    }
+}
+
+impl Conductor {
+    #[allow(clippy::needless_borrowed_reference)]
+    pub fn index(&self, wave_length: &WaveLength) -> (f64, f64) {
+        let l = wave_length.0;
+        match self {
+            &Conductor::Gold => interpolate(&GOLD, l),
+            &Conductor::Copper => interpolate(&COPPER, l),
+            &Conductor::Silver => interpolate(&SILVER, l),
+            &Conductor::Aluminum => interpolate(&ALUMINUM, l),
+            &Conductor::Custom { n: ref n, k: ref k } => {
+                (n.value(wave_length), k.value(wave_length))
+            },
+        }
+    }
+
+    /// Unpolarized Fresnel reflectance of the metal.
+    pub fn reflectance(&self, wave_length: &WaveLength, cosine: f64) -> f64 {
+        let (n, k) = self.index(wave_length);
+        let cos_sq = cosine * cosine;
+        let sin_sq = 1.0 - cos_sq;
+        let t0 = n * n - k * k - sin_sq;
+        let a_sq_plus_b_sq = (t0 * t0 + 4.0 * n * n * k * k).sqrt();
+        let a = (0.5 * (a_sq_plus_b_sq + t0)).max(0.0).sqrt();
+        let t1 = a_sq_plus_b_sq + cos_sq;
+        let t2 = 2.0 * cosine * a;
+        let s = (t1 - t2) / (t1 + t2);
+        let t3 = cos_sq * a_sq_plus_b_sq + sin_sq * sin_sq;
+        let t4 = t2 * sin_sq;
+        let p = s * (t3 - t4) / (t3 + t4);
+        (s + p) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Conductor;
+    use crate::core::WaveLength;
+
+    #[test]
+    fn reflectance() {
+        // normal incidence, ((n - 1)² + k²) / ((n + 1)² + k²)
+        let l = WaveLength(550.0);
+        let (n, k) = Conductor::Gold.index(&l);
+        let r = ((n - 1.0) * (n - 1.0) + k * k) / ((n + 1.0) * (n + 1.0) + k * k);
+        assert!((Conductor::Gold.reflectance(&l, 1.0) - r).abs() < 1e-12);
+        assert!((Conductor::Gold.reflectance(&l, 0.0) - 1.0).abs() < 1e-12);
+
+        // gold is yellow, silver is white
+        let gold = |l| Conductor::Gold.reflectance(&WaveLength(l), 1.0);
+        assert!(gold(650.0) > gold(450.0) + 0.3);
+        let silver = |l| Conductor::Silver.reflectance(&WaveLength(l), 1.0);
+        assert!(silver(450.0) > 0.9 && silver(650.0) > 0.9);
+    }
+}
@@ -0,0 +1,211 @@
+use crate::core::{WaveLength, Event};
+
+use serde::{Serialize, Deserialize};
+
+/// Index of refraction as the function of the wave length, the formulas take it in micrometers.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum Dispersion {
+    /// Borosilicate crown glass, Schott N-BK7.
+    Bk7,
+    FusedSilica,
+    Diamond,
+    /// Water at 20 degrees Celsius.
+    Water,
+    /// `n² = 1 + Σ B λ² / (λ² - C)` over the `(B, C)` terms.
+    Sellmeier(Vec<(f64, f64)>),
+    /// Same index for all the wave lengths.
+    Constant(f64),
+    /// `n = a + b / λ² + c / λ⁴`.
+    Cauchy {
+        a: f64,
+        b: f64,
+        #[serde(default)]
+        c: f64,
+    },
+}
+
+const BK7: [(f64, f64); 3] = [
+    (1.039_612_12, 0.006_000_698_67),
+    (0.231_792_344, 0.020_017_914_4),
+    (1.010_469_45, 103.560_653),
+];
+
+const FUSED_SILICA: [(f64, f64); 3] = [
+    (0.696_166_3, 0.004_679_148_26),
+    (0.407_942_6, 0.013_512_063_1),
+    (0.897_479_4, 97.934_002_5),
+];
+
+const DIAMOND: [(f64, f64); 2] = [(0.3306, 0.030_625), (4.3356, 0.011_236)];
+
+const WATER: [(f64, f64); 4] = [
+    (0.568_402_756_5, 0.005_101_829_712),
+    (0.172_617_739_1, 0.018_211_539_36),
+    (0.020_861_895_78, 0.026_207_222_93),
+    (0.113_074_868_8, 10.697_927_21),
+];
+
+impl Dispersion {
+    #[allow(clippy::needless_borrowed_reference)]
+    pub fn index(&self, wave_length: &WaveLength) -> f64 {
+        let l = wave_length.0 / 1000.0;
+        let l_sq = l * l;
+        let sellmeier = |terms: &[(f64, f64)]| {
+            terms
+                .iter()
+                .fold(1.0, |n_sq, &(b, c)| n_sq + b * l_sq / (l_sq - c))
+                .sqrt()
+        };
+        match self {
+            &Dispersion::Bk7 => sellmeier(&BK7),
+            &Dispersion::FusedSilica => sellmeier(&FUSED_SILICA),
+            &Dispersion::Diamond => sellmeier(&DIAMOND),
+            &Dispersion::Water => sellmeier(&WATER),
+            &Dispersion::Sellmeier(ref terms) => sellmeier(terms.as_ref()),
+            &Dispersion::Constant(n) => n,
+            &Dispersion::Cauchy { a: a, b: b, c: c } => a + b / l_sq + c / (l_sq * l_sq),
+        }
+    }
+}
+
+/// Unpolarized Fresnel reflectance, `eta` is the index of the medium the ray leaves
+/// relative to the one it enters, one for the total internal reflection.
+pub fn reflectance(eta: f64, cosine: f64) -> f64 {
+    let sin_t_sq = eta * eta * (1.0 - cosine * cosine);
+    if sin_t_sq >= 1.0 {
+        return 1.0;
+    }
+    let cos_t = (1.0 - sin_t_sq).sqrt();
+    let s = (eta * cosine - cos_t) / (eta * cosine + cos_t);
+    let p = (cosine - eta * cos_t) / (cosine + eta * cos_t);
+    (s * s + p * p) / 2.0
+}
+
+/// Unpolarized reflectance of the film of the `thickness` in nanometers between the media,
+/// the indices go in the order the ray passes them, the waves reflected by the both sides
+/// of the film interfere.
+pub fn film_reflectance(
+    indices: (f64, f64, f64),
+    thickness: f64,
+    wave_length: &WaveLength,
+    cosine: f64,
+) -> f64 {
+    use std::f64::consts::TAU;
+
+    let (n0, n1, n2) = indices;
+    let sin_sq = 1.0 - cosine * cosine;
+    // the cosines of the angles in the film and behind it, by Snell's law
+    let cos_in = |n: f64| {
+        let sin_sq = sin_sq * (n0 / n) * (n0 / n);
+        if sin_sq < 1.0 {
+            Some((1.0 - sin_sq).sqrt())
+        } else {
+            None
+        }
+    };
+    let (c0, c1, c2) = match (cos_in(n1), cos_in(n2)) {
+        (Some(c1), Some(c2)) => (cosine, c1, c2),
+        _ => return 1.0,
+    };
+
+    let phase = TAU / wave_length.0 * 2.0 * n1 * thickness * c1;
+    let airy = |r01: f64, r12: f64| {
+        let interference = 2.0 * r01 * r12 * phase.cos();
+        (r01 * r01 + r12 * r12 + interference) / (1.0 + r01 * r01 * r12 * r12 + interference)
+    };
+    let s = airy(
+        (n0 * c0 - n1 * c1) / (n0 * c0 + n1 * c1),
+        (n1 * c1 - n2 * c2) / (n1 * c1 + n2 * c2),
+    );
+    let p = airy(
+        (n1 * c0 - n0 * c1) / (n1 * c0 + n0 * c1),
+        (n2 * c1 - n1 * c2) / (n2 * c1 + n1 * c2),
+    );
+    (s + p) / 2.0
+}
+
+/// Reflects with the probability of the Fresnel reflectance, refracts otherwise.
+pub(super) fn fate(eta: f64, cosine: f64, event: f64) -> Event<f64> {
+    choose(reflectance(eta, cosine), eta, event)
+}
+
+/// Reflects with the probability, refracts otherwise.
+pub(super) fn choose(reflectance: f64, eta: f64, event: f64) -> Event<f64> {
+    if event < reflectance {
+        Event::Reflect(0.0)
+    } else {
+        Event::Refract(eta)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Dispersion, reflectance, film_reflectance};
+    use crate::core::WaveLength;
+
+    #[test]
+    fn index() {
+        let d = WaveLength(587.6);
+        assert!((Dispersion::Bk7.index(&d) - 1.5168).abs() < 1e-4);
+        assert!((Dispersion::FusedSilica.index(&d) - 1.4585).abs() < 1e-4);
+        assert!((Dispersion::Diamond.index(&d) - 2.417).abs() < 2e-3);
+        assert!((Dispersion::Water.index(&d) - 1.333).abs() < 1e-3);
+
+        // normal dispersion, blue bends more
+        let blue = Dispersion::Bk7.index(&WaveLength(450.0));
+        let red = Dispersion::Bk7.index(&WaveLength(650.0));
+        assert!(blue > red);
+
+        let cauchy = Dispersion::Cauchy {
+            a: 1.5,
+            b: 0.01,
+            c: 0.0,
+        };
+        assert!((cauchy.index(&WaveLength(500.0)) - 1.54).abs() < 1e-12);
+    }
+
+    #[test]
+    fn fresnel() {
+        // normal incidence, ((n - 1) / (n + 1))² from both sides
+        let n = 1.5;
+        let r = 0.04;
+        assert!((reflectance(1.0 / n, 1.0) - r).abs() < 1e-12);
+        assert!((reflectance(n, 1.0) - r).abs() < 1e-12);
+
+        // Brewster angle kills the p component
+        let brewster = n.atan().cos();
+        let s = reflectance(1.0 / n, brewster);
+        assert!(s > 0.0 && s < r * 2.0);
+
+        // grazing and beyond the critical angle
+        assert!((reflectance(1.0 / n, 0.0) - 1.0).abs() < 1e-12);
+        let critical = (1.0 - 1.0 / (n * n)).sqrt();
+        assert_eq!(reflectance(n, critical * 0.99), 1.0);
+        assert!(reflectance(n, critical * 1.01) < 1.0);
+    }
+
+    #[test]
+    fn film() {
+        let l = WaveLength(550.0);
+        let n = 1.5;
+
+        // no film is the bare surface
+        for &cosine in [1.0, 0.7, 0.2].iter() {
+            let bare = reflectance(1.0 / n, cosine);
+            assert!((film_reflectance((1.0, 1.38, n), 0.0, &l, cosine) - bare).abs() < 1e-12);
+        }
+
+        // quarter wave coating of the geometric mean index cancels the reflection
+        let coating = n.sqrt();
+        let thickness = 550.0 / (4.0 * coating);
+        assert!(film_reflectance((1.0, coating, n), thickness, &l, 1.0) < 1e-12);
+        assert!(film_reflectance((1.0, coating, n), thickness, &WaveLength(450.0), 1.0) > 1e-4);
+
+        // the soap film in the air reflects some colors more than the others
+        let soap = |l| film_reflectance((1.0, 1.33, 1.0), 300.0, &WaveLength(l), 1.0);
+        let (min, max) = (400..700)
+            .map(|l| soap(l as f64))
+            .fold((1.0f64, 0.0f64), |(min, max), r| (min.min(r), max.max(r)));
+        assert!(min < 0.01 && max > 0.05);
+    }
+}
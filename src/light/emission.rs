@@ -0,0 +1,267 @@
+use crate::core::{WaveLength, Spectrum, ColorMatching};
+
+use serde::{Serialize, Deserialize};
+
+/// Spectral shape of the light source, the scale does not matter, see `Emission`.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum Illuminant {
+    /// Planck's law for the temperature in kelvins.
+    Blackbody(f64),
+    /// CIE standard illuminant A, the incandescent lamp.
+    A,
+    /// CIE standard illuminant D50, the horizon daylight.
+    D50,
+    /// CIE standard illuminant D65, the noon daylight.
+    D65,
+    /// CIE fluorescent illuminant F2, the cool white lamp.
+    F2,
+    /// CIE fluorescent illuminant F7, the broadband daylight lamp.
+    F7,
+    /// CIE fluorescent illuminant F11, the narrow tri-band lamp.
+    F11,
+    /// Low pressure sodium lamp.
+    Sodium,
+    /// Low pressure mercury lamp.
+    Mercury,
+    /// Neon glow lamp, the red and orange lines.
+    Neon,
+    /// The `(wave length, power)` lines, each spread by the gaussian of `LINE_WIDTH`.
+    Lines(Vec<(f64, f64)>),
+    /// The measured spectral power distribution.
+    Spectrum(Spectrum),
+}
+
+/// Standard deviation of the spectral lines in nanometers, wide enough for the wave lengths
+/// sampled a nanometer apart to see them.
+pub const LINE_WIDTH: f64 = 1.0;
+
+/// The spectral shape scaled so the luminance, the CIE Y, of the emitted light is one.
+///
+/// The luminance is always of the CIE 1964 standard observer, whatever observer
+/// the `Tracing` renders with, so the other observer changes the color of the light
+/// and not its power. In the scene file it is the `Illuminant`, the scale is computed
+/// on loading.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(from = "Illuminant", into = "Illuminant")]
+pub struct Emission {
+    illuminant: Illuminant,
+    scale: f64,
+}
+
+impl From<Illuminant> for Emission {
+    fn from(illuminant: Illuminant) -> Self {
+        let luminance = (360..=830)
+            .map(|l| {
+                let l = WaveLength(l as f64);
+                illuminant.value(&l) * ColorMatching::Cie1964.xyz(&l).tuple().1
+            })
+            .sum::<f64>();
+        Emission {
+            illuminant: illuminant,
+            scale: if luminance > 0.0 {
+                1.0 / luminance
+            } else {
+                0.0
+            },
+        }
+    }
+}
+
+impl From<Emission> for Illuminant {
+    fn from(emission: Emission) -> Self {
+        emission.illuminant
+    }
+}
+
+impl Emission {
+    pub fn value(&self, wave_length: &WaveLength) -> f64 {
+        self.illuminant.value(wave_length) * self.scale
+    }
+}
+
+impl Illuminant {
+    /// Relative spectral power, not normalized.
+    #[allow(clippy::needless_borrowed_reference)]
+    pub fn value(&self, wave_length: &WaveLength) -> f64 {
+        let l = wave_length.0;
+        match self {
+            &Illuminant::Blackbody(t) => planck(l, t),
+            // the definition of the illuminant A, the blackbody of the old temperature scale
+            &Illuminant::A => {
+                let c: f64 = 1.435e7 / 2848.0;
+                100.0 * (560.0 / l).powi(5) * (c / 560.0).exp_m1() / (c / l).exp_m1()
+            },
+            &Illuminant::D50 => table(&D50, 380.0, 10.0, l),
+            &Illuminant::D65 => table(&D65, 380.0, 10.0, l),
+            &Illuminant::F2 => table(&F2, 380.0, 5.0, l),
+            &Illuminant::F7 => table(&F7, 380.0, 5.0, l),
+            &Illuminant::F11 => table(&F11, 380.0, 5.0, l),
+            &Illuminant::Sodium => lines(&SODIUM, l),
+            &Illuminant::Mercury => lines(&MERCURY, l),
+            &Illuminant::Neon => lines(&NEON, l),
+            &Illuminant::Lines(ref v) => lines(v.as_ref(), l),
+            &Illuminant::Spectrum(ref spectrum) => spectrum.value(wave_length),
+        }
+    }
+}
+
+/// Planck's law, in the arbitrary units, the wave length is in nanometers.
+fn planck(l: f64, t: f64) -> f64 {
+    // second radiation constant in nanometer kelvins
+    let c = 1.438_776_9e7;
+    let micrometers = l / 1000.0;
+    1.0 / (micrometers.powi(5) * (c / (l * t)).exp_m1())
+}
+
+/// Linear between the values tabulated with the step from the first wave length,
+/// zero outside.
+fn table(values: &[f64], first: f64, step: f64, l: f64) -> f64 {
+    let x = (l - first) / step;
+    if x < 0.0 || x > (values.len() - 1) as f64 {
+        return 0.0;
+    }
+    let i = (x as usize).min(values.len() - 2);
+    let s = x - i as f64;
+    values[i] * (1.0 - s) + values[i + 1] * s
+}
+
+fn lines(lines: &[(f64, f64)], l: f64) -> f64 {
+    use std::f64::consts::TAU;
+
+    let norm = 1.0 / (LINE_WIDTH * TAU.sqrt());
+    lines
+        .iter()
+        .map(|&(center, power)| {
+            let x = (l - center) / LINE_WIDTH;
+            power * norm * (-0.5 * x * x).exp()
+        })
+        .sum()
+}
+
+// CIE tables from 380 to 780 nanometers
+const D50: [f64; 41] = [
+    24.49, 29.87, 49.31, 56.51, 60.03, 57.82, 74.82, 87.25, 90.61, 91.37, 95.11, 91.96, 95.72,
+    96.61, 97.13, 102.10, 100.75, 102.32, 100.00, 97.74, 98.92, 93.50, 97.69, 99.27, 99.04, 95.72,
+    98.86, 95.67, 98.19, 103.00, 99.13, 87.38, 91.60, 92.89, 76.85, 86.51, 92.58, 78.23, 57.69,
+    82.92, 78.27,
+];
+
+const D65: [f64; 41] = [
+    49.9755, 54.6482, 82.7549, 91.486, 93.4318, 86.6823, 104.865, 117.008, 117.812, 114.861,
+    115.923, 108.811, 109.354, 107.802, 104.790, 107.689, 104.405, 104.046, 100.000, 96.3342,
+    95.788, 88.6856, 90.0062, 89.5991, 87.6987, 83.2886, 83.6992, 80.0268, 80.2146, 82.2778,
+    78.2842, 69.7213, 71.6091, 74.349, 61.604, 69.8856, 75.087, 63.5927, 46.4182, 66.8054, 63.3828,
+];
+
+const F2: [f64; 81] = [
+    1.18, 1.48, 1.84, 2.15, 3.44, 15.69, 3.85, 3.74, 4.19, 4.62, 5.06, 34.98, 11.81, 6.27, 6.63,
+    6.93, 7.19, 7.40, 7.54, 7.62, 7.65, 7.62, 7.62, 7.45, 7.28, 7.15, 7.05, 7.04, 7.16, 7.47, 8.04,
+    8.88, 10.01, 24.88, 16.64, 14.59, 16.16, 17.56, 18.62, 21.47, 22.79, 19.29, 18.66, 17.73,
+    16.54, 15.21, 13.80, 12.36, 10.95, 9.65, 8.40, 7.32, 6.31, 5.43, 4.68, 4.02, 3.45, 2.96, 2.55,
+    2.19, 1.89, 1.64, 1.53, 1.27, 1.10, 0.99, 0.88, 0.76, 0.68, 0.61, 0.56, 0.54, 0.51, 0.47, 0.47,
+    0.43, 0.46, 0.47, 0.40, 0.33, 0.27,
+];
+
+const F7: [f64; 81] = [
+    2.56, 3.18, 3.84, 4.53, 6.15, 19.37, 7.37, 7.05, 7.71, 8.41, 9.15, 44.14, 17.52, 11.35, 12.00,
+    12.58, 13.08, 13.45, 13.71, 13.88, 13.95, 13.93, 13.82, 13.64, 13.43, 13.25, 13.08, 12.93,
+    12.78, 12.60, 12.44, 12.33, 12.26, 29.52, 17.05, 12.44, 12.58, 12.72, 12.83, 15.46, 16.75,
+    12.83, 12.67, 12.45, 12.19, 11.89, 11.60, 11.35, 11.12, 10.95, 10.76, 10.42, 10.11, 10.04,
+    10.02, 10.11, 9.87, 8.65, 7.27, 6.44, 5.83, 5.41, 5.04, 4.57, 4.12, 3.77, 3.46, 3.08, 2.73,
+    2.47, 2.25, 2.06, 1.90, 1.75, 1.62, 1.54, 1.45, 1.32, 1.17, 0.99, 0.81,
+];
+
+const F11: [f64; 81] = [
+    0.91, 0.63, 0.46, 0.37, 1.29, 12.68, 1.59, 1.79, 2.46, 3.33, 4.49, 33.94, 12.13, 6.95, 7.19,
+    7.12, 6.72, 6.13, 5.46, 4.79, 5.66, 14.29, 14.96, 8.97, 4.72, 2.33, 1.47, 1.10, 0.89, 0.83,
+    1.18, 4.90, 39.59, 72.84, 32.61, 7.52, 2.83, 1.96, 1.67, 4.43, 11.28, 14.76, 12.73, 9.74, 7.33,
+    9.72, 55.27, 42.58, 13.18, 13.16, 12.26, 5.11, 2.07, 2.34, 3.58, 3.01, 2.48, 2.14, 1.54, 1.33,
+    1.46, 1.94, 2.00, 1.20, 1.35, 4.10, 5.58, 2.51, 0.57, 0.27, 0.23, 0.21, 0.24, 0.24, 0.20, 0.24,
+    0.32, 0.26, 0.16, 0.12, 0.09,
+];
+
+// the visible lines of the gas discharge, `(wave length, relative intensity)`
+const SODIUM: [(f64, f64); 2] = [(588.995, 2.0), (589.592, 1.0)];
+
+const MERCURY: [(f64, f64); 5] = [
+    (404.656, 1800.0),
+    (435.833, 4000.0),
+    (546.074, 1100.0),
+    (576.960, 240.0),
+    (579.066, 280.0),
+];
+
+const NEON: [(f64, f64); 20] = [
+    (585.249, 20000.0),
+    (588.190, 10000.0),
+    (594.483, 5000.0),
+    (603.000, 1000.0),
+    (607.434, 10000.0),
+    (609.616, 10000.0),
+    (614.306, 10000.0),
+    (616.359, 10000.0),
+    (621.728, 10000.0),
+    (626.650, 10000.0),
+    (633.443, 10000.0),
+    (638.299, 10000.0),
+    (640.225, 20000.0),
+    (650.653, 15000.0),
+    (653.288, 1000.0),
+    (659.895, 10000.0),
+    (667.828, 5000.0),
+    (671.704, 7000.0),
+    (692.947, 100_000.0),
+    (703.241, 85000.0),
+];
+
+#[cfg(test)]
+mod test {
+    use super::{Emission, Illuminant};
+    use crate::core::WaveLength;
+
+    fn luminance(emission: &Emission) -> f64 {
+        (360..=830)
+            .map(|l| {
+                let l = WaveLength(l as f64);
+                emission.value(&l) * l.xyz().tuple().1
+            })
+            .sum()
+    }
+
+    #[test]
+    fn normalized() {
+        let illuminants = vec![
+            Illuminant::Blackbody(2000.0),
+            Illuminant::Blackbody(10000.0),
+            Illuminant::A,
+            Illuminant::D50,
+            Illuminant::D65,
+            Illuminant::F2,
+            Illuminant::F7,
+            Illuminant::F11,
+            Illuminant::Sodium,
+            Illuminant::Mercury,
+            Illuminant::Neon,
+        ];
+        for illuminant in illuminants {
+            let emission = Emission::from(illuminant);
+            assert!((luminance(&emission) - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn shape() {
+        // the standard illuminants are tabulated relative to 560 nm
+        let value = |illuminant: Illuminant, l| illuminant.value(&WaveLength(l));
+        assert!((value(Illuminant::A, 560.0) - 100.0).abs() < 1e-9);
+        assert!((value(Illuminant::D65, 560.0) - 100.0).abs() < 1e-9);
+
+        // the illuminant A is close to the blackbody of 2856 K
+        let ratio = |l| value(Illuminant::A, l) / value(Illuminant::Blackbody(2856.0), l);
+        assert!((ratio(450.0) / ratio(650.0) - 1.0).abs() < 1e-3);
+
+        // the sodium lamp is yellow
+        let sodium: Emission = serde_json::from_str("\"Sodium\"").unwrap();
+        assert!(sodium.value(&WaveLength(589.0)) > 100.0 * sodium.value(&WaveLength(550.0)));
+    }
+}
@@ -0,0 +1,514 @@
+use super::primitive::Surface;
+use crate::core::{V3, Ray, Scene, Intersect, Light};
+
+use serde::{Serialize, Deserialize};
+use num::Float;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Bounds<C>
+where
+    C: Float,
+{
+    min: V3<C>,
+    max: V3<C>,
+}
+
+fn coordinate<C>(v: &V3<C>, axis: usize) -> C
+where
+    C: Float,
+{
+    match axis {
+        0 => v.x(),
+        1 => v.y(),
+        _ => v.z(),
+    }
+}
+
+impl<C> Bounds<C>
+where
+    C: Float,
+{
+    pub fn new(min: V3<C>, max: V3<C>) -> Self {
+        Bounds { min: min, max: max }
+    }
+
+    /// Contains nothing, neutral element of `union`.
+    pub fn empty() -> Self {
+        let inf = C::infinity();
+        Bounds {
+            min: V3::new(inf, inf, inf),
+            max: V3::new(-inf, -inf, -inf),
+        }
+    }
+
+    pub fn enclose<'a, I>(points: I) -> Self
+    where
+        I: Iterator<Item = &'a V3<C>>,
+        C: 'a,
+    {
+        points.fold(Self::empty(), |bounds, point| Bounds {
+            min: bounds.min.min(point),
+            max: bounds.max.max(point),
+        })
+    }
+
+    pub fn min(&self) -> &V3<C> {
+        &self.min
+    }
+
+    pub fn max(&self) -> &V3<C> {
+        &self.max
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Bounds {
+            min: self.min.min(&other.min),
+            max: self.max.max(&other.max),
+        }
+    }
+
+    pub fn centroid(&self) -> V3<C> {
+        &(&self.min + &self.max) / C::from(2.0).unwrap()
+    }
+
+    pub fn surface_area(&self) -> C {
+        let d = &self.max - &self.min;
+        let (x, y, z) = (d.x(), d.y(), d.z());
+        if x < C::zero() || y < C::zero() || z < C::zero() {
+            C::zero()
+        } else {
+            C::from(2.0).unwrap() * (x * y + y * z + z * x)
+        }
+    }
+
+    fn longest_axis(&self) -> usize {
+        let d = &self.max - &self.min;
+        if d.x() >= d.y() && d.x() >= d.z() {
+            0
+        } else if d.y() >= d.z() {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Time when the ray enters the box, zero if it starts inside.
+    /// Takes the inverse of the ray direction, computed once per ray.
+    fn hit(&self, position: &V3<C>, inverse: &V3<C>) -> Option<C> {
+        let mut near = C::zero();
+        let mut far = C::infinity();
+        for axis in 0..3 {
+            let p = coordinate(position, axis);
+            let i = coordinate(inverse, axis);
+            let t0 = (coordinate(&self.min, axis) - p) * i;
+            let t1 = (coordinate(&self.max, axis) - p) * i;
+            // `Float::min` and `Float::max` skip NaN, which appears for the ray lying on a face
+            near = near.max(t0.min(t1));
+            far = far.min(t0.max(t1));
+        }
+        if near <= far {
+            Some(near)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Node<C>
+where
+    C: Float,
+{
+    bounds: Bounds<C>,
+    // for a leaf the range of items, for an interior node `count` is zero,
+    // the left child immediately follows the node and `start` is the right child
+    start: usize,
+    count: usize,
+    axis: usize,
+}
+
+struct Primitive<C>
+where
+    C: Float,
+{
+    index: usize,
+    bounds: Bounds<C>,
+    centroid: V3<C>,
+}
+
+/// Hierarchy of the boxes over the items with bounds, built with the surface area heuristic
+/// and stored as a flat array of nodes in depth first order. The items are reordered
+/// so that every leaf covers a contiguous range of them.
+#[derive(Clone)]
+pub(super) struct Hierarchy<C>
+where
+    C: Float,
+{
+    nodes: Vec<Node<C>>,
+}
+
+const BINS: usize = 12;
+const MAX_LEAF: usize = 4;
+const MAX_DEPTH: usize = 64;
+
+impl<C> Hierarchy<C>
+where
+    C: Float,
+{
+    /// Returns the hierarchy and the order of the items, the item at the position `i`
+    /// of the reordered sequence is the item `order[i]` of the original one.
+    pub(super) fn new(bounds: Vec<Bounds<C>>) -> (Self, Vec<usize>) {
+        let mut primitives = bounds
+            .into_iter()
+            .enumerate()
+            .map(|(index, bounds)| Primitive {
+                index: index,
+                centroid: bounds.centroid(),
+                bounds: bounds,
+            })
+            .collect::<Vec<_>>();
+
+        let mut nodes = Vec::new();
+        if !primitives.is_empty() {
+            Self::build(primitives.as_mut(), 0, 0, &mut nodes);
+        }
+        let order = primitives.iter().map(|primitive| primitive.index).collect();
+        (Hierarchy { nodes: nodes }, order)
+    }
+
+    fn build(
+        primitives: &mut [Primitive<C>],
+        offset: usize,
+        depth: usize,
+        nodes: &mut Vec<Node<C>>,
+    ) -> usize {
+        let bounds = primitives
+            .iter()
+            .fold(Bounds::empty(), |b, p| b.union(&p.bounds));
+        let index = nodes.len();
+        nodes.push(Node {
+            bounds: bounds.clone(),
+            start: offset,
+            count: primitives.len(),
+            axis: 0,
+        });
+        if primitives.len() <= 1 || depth + 1 >= MAX_DEPTH {
+            return index;
+        }
+
+        let centroids = primitives.iter().fold(Bounds::empty(), |b, p| Bounds {
+            min: b.min.min(&p.centroid),
+            max: b.max.max(&p.centroid),
+        });
+        let axis = centroids.longest_axis();
+        let low = coordinate(&centroids.min, axis);
+        let extent = coordinate(&centroids.max, axis) - low;
+        if extent <= C::zero() {
+            return index;
+        }
+
+        let bins = C::from(BINS).unwrap();
+        let bin = |p: &Primitive<C>| -> usize {
+            let b = ((coordinate(&p.centroid, axis) - low) / extent * bins)
+                .to_usize()
+                .unwrap_or(0);
+            b.min(BINS - 1)
+        };
+
+        let mut counts = [0usize; BINS];
+        let mut boxes = (0..BINS).map(|_| Bounds::empty()).collect::<Vec<_>>();
+        for p in primitives.iter() {
+            let b = bin(p);
+            counts[b] += 1;
+            boxes[b] = boxes[b].union(&p.bounds);
+        }
+
+        // cost of the split after the bin `k`, in units of the parent area
+        let mut best = None;
+        for k in 1..BINS {
+            let (left, right) = (boxes[..k].iter(), boxes[k..].iter());
+            let left_area = left.fold(Bounds::empty(), |a, b| a.union(b)).surface_area();
+            let right_area = right
+                .fold(Bounds::empty(), |a, b| a.union(b))
+                .surface_area();
+            let left_count = C::from(counts[..k].iter().sum::<usize>()).unwrap();
+            let right_count = C::from(counts[k..].iter().sum::<usize>()).unwrap();
+            let cost = left_area * left_count + right_area * right_count;
+            match &best {
+                &Some((_, c)) if c <= cost => (),
+                _ => best = Some((k, cost)),
+            }
+        }
+
+        let (split, cost) = best.unwrap();
+        let area = bounds.surface_area();
+        let leaf_cost = C::from(primitives.len()).unwrap();
+        let split_cost = if area > C::zero() {
+            C::one() + cost / area
+        } else {
+            C::infinity()
+        };
+        if primitives.len() <= MAX_LEAF && leaf_cost <= split_cost {
+            return index;
+        }
+
+        // partition in place, fall back to the median if the bins did not separate
+        let mut middle = 0;
+        for i in 0..primitives.len() {
+            if bin(&primitives[i]) < split {
+                primitives.swap(i, middle);
+                middle += 1;
+            }
+        }
+        if middle == 0 || middle == primitives.len() {
+            primitives.sort_by(|a, b| {
+                coordinate(&a.centroid, axis)
+                    .partial_cmp(&coordinate(&b.centroid, axis))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+            middle = primitives.len() / 2;
+        }
+
+        let (left, right) = primitives.split_at_mut(middle);
+        Self::build(left, offset, depth + 1, nodes);
+        let right = Self::build(right, offset + middle, depth + 1, nodes);
+        nodes[index].start = right;
+        nodes[index].count = 0;
+        nodes[index].axis = axis;
+        index
+    }
+
+    /// Nearest item the ray hits before `best_time`, `intersect` gives the time
+    /// of the hit and the details of the item at the position in the reordered sequence.
+    pub(super) fn nearest<T, F>(&self, ray: &Ray<C>, best_time: C, intersect: F) -> Option<T>
+    where
+        F: Fn(usize) -> Option<(C, T)>,
+    {
+        let mut best = None;
+        let mut best_time = best_time;
+
+        if self.nodes.is_empty() {
+            return best;
+        }
+
+        let d = ray.direction();
+        let one = C::one();
+        let inverse = V3::new(one / d.x(), one / d.y(), one / d.z());
+
+        let mut stack = [0; MAX_DEPTH];
+        let mut top = 1;
+        while top > 0 {
+            top -= 1;
+            let node = &self.nodes[stack[top]];
+            match node.bounds.hit(ray.position(), &inverse) {
+                Some(time) if time <= best_time => (),
+                _ => continue,
+            }
+
+            if node.count > 0 {
+                for index in node.start..(node.start + node.count) {
+                    if let Some((time, info)) = intersect(index) {
+                        if time < best_time {
+                            best_time = time;
+                            best = Some(info);
+                        }
+                    }
+                }
+            } else {
+                // visit the nearer child first
+                let (left, right) = (stack[top] + 1, node.start);
+                let (near, far) = if coordinate(d, node.axis) >= C::zero() {
+                    (left, right)
+                } else {
+                    (right, left)
+                };
+                stack[top] = far;
+                stack[top + 1] = near;
+                top += 2;
+            }
+        }
+
+        best
+    }
+}
+
+/// Bounding volume hierarchy over the surfaces.
+/// Unbounded surfaces, like planes, are kept aside and tested for every ray.
+#[derive(Clone, Deserialize)]
+#[serde(from = "Vec<S>")]
+pub struct Bvh<S, C>
+where
+    S: Surface<C>,
+    C: Float,
+{
+    surfaces: Vec<S>,
+    unbounded: Vec<S>,
+    hierarchy: Hierarchy<C>,
+}
+
+impl<S, C> From<Vec<S>> for Bvh<S, C>
+where
+    S: Surface<C>,
+    C: Float,
+{
+    fn from(surfaces: Vec<S>) -> Self {
+        Bvh::new(surfaces)
+    }
+}
+
+impl<S, C> Bvh<S, C>
+where
+    S: Surface<C>,
+    C: Float,
+{
+    pub fn new(surfaces: Vec<S>) -> Self {
+        let mut bounded = Vec::with_capacity(surfaces.len());
+        let mut bounds = Vec::with_capacity(surfaces.len());
+        let mut unbounded = Vec::new();
+        for surface in surfaces {
+            match surface.bounds() {
+                Some(b) => {
+                    bounds.push(b);
+                    bounded.push(Some(surface));
+                },
+                None => unbounded.push(surface),
+            }
+        }
+
+        let (hierarchy, order) = Hierarchy::new(bounds);
+        let surfaces = order
+            .into_iter()
+            .map(|index| bounded[index].take().unwrap())
+            .collect();
+
+        Bvh {
+            surfaces: surfaces,
+            unbounded: unbounded,
+            hierarchy: hierarchy,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.surfaces.len() + self.unbounded.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn nearest<'a>(&'a self, ray: &Ray<C>) -> Option<(&'a S, S::Info)> {
+        let best = Surface::find_intersect(self.unbounded.as_ref(), ray);
+        let best_time = best
+            .as_ref()
+            .map(|(_, info)| S::time(info))
+            .unwrap_or_else(C::infinity);
+
+        let bounded = self.hierarchy.nearest(ray, best_time, |index| {
+            let surface = &self.surfaces[index];
+            surface
+                .intersect(ray)
+                .map(|info| (S::time(&info), (surface, info)))
+        });
+        bounded.or(best)
+    }
+}
+
+impl<S, C> Scene<C> for Bvh<S, C>
+where
+    S: Surface<C>,
+    C: Float,
+{
+    type Material = S::Material;
+
+    fn find_intersect<'a>(&'a self, ray: &Ray<C>) -> Option<Intersect<'a, Self::Material, C>> {
+        self.nearest(ray).map(|(this, info)| this.result(ray, info))
+    }
+
+    fn lights(&self) -> Vec<Light<C>> {
+        self.surfaces
+            .iter()
+            .chain(self.unbounded.iter())
+            .flat_map(Surface::light)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Bvh;
+    use crate::{
+        core::{V3, Ray, Scene, WaveLength},
+        tree::{Sphere, Plane},
+        light::CustomMaterial,
+    };
+    use rand::Rng;
+
+    #[test]
+    fn same_as_linear() {
+        let mut rng = rand::thread_rng();
+        let mut scene = (0..1000)
+            .map(|_| {
+                let center = V3::new(
+                    rng.gen_range(-50.0..50.0),
+                    rng.gen_range(-50.0..50.0),
+                    rng.gen_range(-50.0..50.0),
+                );
+                Sphere::new(
+                    center,
+                    rng.gen_range(0.1..2.0),
+                    CustomMaterial::DiffuseWhite,
+                )
+            })
+            .collect::<Vec<_>>();
+        scene.push(Sphere::new(
+            V3::new(0.0, 0.0, 0.0),
+            100.0,
+            CustomMaterial::DiffuseWhite,
+        ));
+        let bvh = Bvh::new(scene.clone());
+
+        for _ in 0..1000 {
+            let position = V3::new(
+                rng.gen_range(-55.0..55.0),
+                rng.gen_range(-55.0..55.0),
+                rng.gen_range(-55.0..55.0),
+            );
+            let direction = V3::new(
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+            )
+            .normalize();
+            let ray = Ray::new(position, direction, WaveLength(550.0));
+            let expected = scene.find_intersect(&ray).unwrap();
+            let actual = bvh.find_intersect(&ray).unwrap();
+            assert_eq!(expected.side.outer(), actual.side.outer());
+            let delta = &expected.position - &actual.position;
+            assert!((&delta * &delta) < 1e-12);
+        }
+    }
+
+    #[test]
+    fn unbounded() {
+        let scene = vec![
+            Plane::new(
+                V3::new(0.0, -1.0, 0.0),
+                V3::new(0.0, 1.0, 0.0),
+                CustomMaterial::DiffuseWhite,
+            ),
+            Plane::new(
+                V3::new(0.0, 1.0, 0.0),
+                V3::new(0.0, -1.0, 0.0),
+                CustomMaterial::DiffuseWhite,
+            ),
+        ];
+        let bvh = Bvh::new(scene);
+        let ray = Ray::new(
+            V3::new(0.0, 0.0, 0.0),
+            V3::new(0.0, 1.0, 0.0),
+            WaveLength(550.0),
+        );
+        let result = bvh.find_intersect(&ray).unwrap();
+        assert!((result.position.y() - 1.0).abs() < 1e-12);
+    }
+}
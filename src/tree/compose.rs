@@ -0,0 +1,312 @@
+use super::{
+    primitive::{
+        Surface, Sphere, SphereInfo, Plane, PlaneInfo, AxisAlignedBox, AxisAlignedBoxInfo,
+    },
+    mesh::{Triangle, TriangleInfo, Mesh, MeshInfo},
+    bvh::Bounds,
+};
+use crate::core::{Ray, Scene, Intersect, Material, Light};
+
+use std::cmp::Ordering;
+use serde::{Deserialize, Deserializer, de::Error};
+use serde_json::{Value, Map};
+use num::Float;
+
+/// Any of the primitives, allows to mix them in one scene.
+/// In the scene file each object is recognized by its fields,
+/// so the plain list of spheres is still a valid scene.
+#[derive(Clone)]
+pub enum Primitive<M, C>
+where
+    M: Material<C>,
+    C: Float,
+{
+    Sphere(Sphere<M, C>),
+    Plane(Plane<M, C>),
+    AxisAlignedBox(AxisAlignedBox<M, C>),
+    Triangle(Triangle<M, C>),
+    Mesh(Mesh<M, C>),
+}
+
+// the field telling the primitive apart, in the order they are checked
+const FIELDS: [(&str, &str); 5] = [
+    ("radius", "Sphere"),
+    ("normal", "Plane"),
+    ("min", "AxisAlignedBox"),
+    ("vertices", "Triangle"),
+    ("obj", "Mesh"),
+];
+
+impl<'de, M, C> Deserialize<'de> for Primitive<M, C>
+where
+    M: Material<C> + Deserialize<'de>,
+    C: Float + Deserialize<'de>,
+{
+    // the object is buffered to look at its fields first, the error of the recognized
+    // primitive is reported as it is rather than the mismatch of all the variants
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let object = Map::<String, Value>::deserialize(deserializer)?;
+        let name = FIELDS
+            .iter()
+            .find(|&&(field, _)| object.contains_key(field))
+            .map(|&(_, name)| name)
+            .ok_or_else(|| {
+                D::Error::custom(
+                    "primitive is not recognized, expected the field radius, normal, min, \
+                     vertices or obj",
+                )
+            })?;
+        let value = Value::Object(object);
+        let wrap = |e: serde_json::Error| D::Error::custom(format!("{}: {}", name, e));
+        match name {
+            "Sphere" => Sphere::deserialize(value).map(Primitive::Sphere),
+            "Plane" => Plane::deserialize(value).map(Primitive::Plane),
+            "AxisAlignedBox" => AxisAlignedBox::deserialize(value).map(Primitive::AxisAlignedBox),
+            "Triangle" => Triangle::deserialize(value).map(Primitive::Triangle),
+            _ => Mesh::deserialize(value).map(Primitive::Mesh),
+        }
+        .map_err(wrap)
+    }
+}
+
+enum Info<C>
+where
+    C: Float,
+{
+    Sphere(SphereInfo<C>),
+    Plane(PlaneInfo<C>),
+    AxisAlignedBox(AxisAlignedBoxInfo<C>),
+    Triangle(TriangleInfo<C>),
+    Mesh(MeshInfo<C>),
+}
+
+pub struct PrimitiveInfo<C>
+where
+    C: Float,
+{
+    time: C,
+    info: Info<C>,
+}
+
+impl<C> PartialEq for PrimitiveInfo<C>
+where
+    C: Float,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.time.eq(&other.time)
+    }
+}
+
+impl<C> PartialOrd for PrimitiveInfo<C>
+where
+    C: Float,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.time.partial_cmp(&other.time)
+    }
+}
+
+#[allow(clippy::needless_borrowed_reference)]
+impl<M, C> Surface<C> for Primitive<M, C>
+where
+    M: Material<C>,
+    C: Float,
+{
+    type Info = PrimitiveInfo<C>;
+    type Material = M;
+
+    fn intersect(&self, ray: &Ray<C>) -> Option<Self::Info> {
+        fn wrap<S, C, F>(surface: &S, ray: &Ray<C>, f: F) -> Option<PrimitiveInfo<C>>
+        where
+            S: Surface<C>,
+            C: Float,
+            F: FnOnce(S::Info) -> Info<C>,
+        {
+            surface.intersect(ray).map(|info| PrimitiveInfo {
+                time: S::time(&info),
+                info: f(info),
+            })
+        }
+
+        match self {
+            &Primitive::Sphere(ref s) => wrap(s, ray, Info::Sphere),
+            &Primitive::Plane(ref s) => wrap(s, ray, Info::Plane),
+            &Primitive::AxisAlignedBox(ref s) => wrap(s, ray, Info::AxisAlignedBox),
+            &Primitive::Triangle(ref s) => wrap(s, ray, Info::Triangle),
+            &Primitive::Mesh(ref s) => wrap(s, ray, Info::Mesh),
+        }
+    }
+
+    fn result<'a>(&'a self, ray: &Ray<C>, info: Self::Info) -> Intersect<'a, Self::Material, C> {
+        match (self, info.info) {
+            (&Primitive::Sphere(ref s), Info::Sphere(info)) => s.result(ray, info),
+            (&Primitive::Plane(ref s), Info::Plane(info)) => s.result(ray, info),
+            (&Primitive::AxisAlignedBox(ref s), Info::AxisAlignedBox(info)) => s.result(ray, info),
+            (&Primitive::Triangle(ref s), Info::Triangle(info)) => s.result(ray, info),
+            (&Primitive::Mesh(ref s), Info::Mesh(info)) => s.result(ray, info),
+            _ => panic!("the intersection info belongs to another primitive"),
+        }
+    }
+
+    fn time(info: &Self::Info) -> C {
+        info.time
+    }
+
+    fn bounds(&self) -> Option<Bounds<C>> {
+        match self {
+            &Primitive::Sphere(ref s) => s.bounds(),
+            &Primitive::Plane(ref s) => s.bounds(),
+            &Primitive::AxisAlignedBox(ref s) => s.bounds(),
+            &Primitive::Triangle(ref s) => s.bounds(),
+            &Primitive::Mesh(ref s) => s.bounds(),
+        }
+    }
+
+    fn light(&self) -> Option<Light<C>> {
+        match self {
+            &Primitive::Sphere(ref s) => s.light(),
+            _ => None,
+        }
+    }
+}
+
+/// Two scenes sharing the material type seen as one, the nearest hit wins.
+#[derive(Clone)]
+pub struct Union<A, B>(pub A, pub B);
+
+impl<A, B, C> Scene<C> for Union<A, B>
+where
+    A: Scene<C>,
+    B: Scene<C, Material = A::Material>,
+    C: Float,
+{
+    type Material = A::Material;
+
+    fn find_intersect<'a>(&'a self, ray: &Ray<C>) -> Option<Intersect<'a, Self::Material, C>> {
+        let distance = |intersect: &Intersect<'a, Self::Material, C>| -> C {
+            &(&intersect.position - ray.position()) * ray.direction()
+        };
+
+        match (self.0.find_intersect(ray), self.1.find_intersect(ray)) {
+            (Some(a), Some(b)) => {
+                if distance(&b) < distance(&a) {
+                    Some(b)
+                } else {
+                    Some(a)
+                }
+            },
+            (a, None) => a,
+            (None, b) => b,
+        }
+    }
+
+    fn lights(&self) -> Vec<Light<C>> {
+        let mut lights = self.0.lights();
+        lights.extend(self.1.lights());
+        lights
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Primitive, Union};
+    use crate::{
+        core::{V3, Ray, Scene, WaveLength},
+        tree::{Sphere, Plane},
+        light::CustomMaterial,
+    };
+
+    #[test]
+    fn mixed() {
+        let scene: Vec<Primitive<CustomMaterial, f64>> = serde_json::from_str(
+            r#"[
+                {
+                    "center": { "x": 0.0, "y": 0.0, "z": 5.0 },
+                    "radius": 1.0,
+                    "material": "Mirror"
+                },
+                {
+                    "point": { "x": 0.0, "y": 0.0, "z": 10.0 },
+                    "normal": { "x": 0.0, "y": 0.0, "z": -1.0 },
+                    "material": "DiffuseWhite"
+                }
+            ]"#,
+        )
+        .unwrap();
+        match (&scene[0], &scene[1]) {
+            (&Primitive::Sphere(_), &Primitive::Plane(_)) => (),
+            _ => panic!("wrong primitives"),
+        }
+
+        let ray = Ray::new(
+            V3::new(0.0, 0.0, 0.0),
+            V3::new(0.0, 0.0, 1.0),
+            WaveLength(550.0),
+        );
+        let result = scene.find_intersect(&ray).unwrap();
+        assert!((result.position.z() - 4.0).abs() < 1e-12);
+
+        let ray = Ray::new(
+            V3::new(0.0, 2.0, 0.0),
+            V3::new(0.0, 0.0, 1.0),
+            WaveLength(550.0),
+        );
+        let result = scene.find_intersect(&ray).unwrap();
+        assert!((result.position.z() - 10.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn errors() {
+        let error = |json: &str| {
+            serde_json::from_str::<Vec<Primitive<CustomMaterial, f64>>>(json)
+                .err()
+                .unwrap()
+                .to_string()
+        };
+
+        let e = error(r#"[{ "obj": "missing.obj", "materials": {} }]"#);
+        assert!(e.starts_with("Mesh: cannot read obj file"), "{}", e);
+
+        let e = error(
+            r#"[{ "center": { "x": 0.0, "y": 0.0, "z": 0.0 }, "radius": 1.0, "material": "Wood" }]"#,
+        );
+        assert!(e.starts_with("Sphere: unknown variant `Wood`"), "{}", e);
+
+        let e = error(r#"[{ "centre": { "x": 0.0, "y": 0.0, "z": 0.0 } }]"#);
+        assert!(e.starts_with("primitive is not recognized"), "{}", e);
+    }
+
+    #[test]
+    fn union() {
+        let spheres = vec![Sphere::new(
+            V3::new(0.0, 0.0, 5.0),
+            1.0,
+            CustomMaterial::Mirror,
+        )];
+        let planes = vec![Plane::new(
+            V3::new(0.0, 0.0, 3.0),
+            V3::new(0.0, 0.0, -1.0),
+            CustomMaterial::DiffuseWhite,
+        )];
+        let scene = Union(spheres, planes);
+
+        let ray = Ray::new(
+            V3::new(0.0, 0.0, 0.0),
+            V3::new(0.0, 0.0, 1.0),
+            WaveLength(550.0),
+        );
+        let result = scene.find_intersect(&ray).unwrap();
+        assert!((result.position.z() - 3.0).abs() < 1e-12);
+
+        let ray = Ray::new(
+            V3::new(0.0, 0.0, 10.0),
+            V3::new(0.0, 0.0, -1.0),
+            WaveLength(550.0),
+        );
+        let result = scene.find_intersect(&ray).unwrap();
+        assert!((result.position.z() - 6.0).abs() < 1e-12);
+    }
+}
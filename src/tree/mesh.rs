@@ -0,0 +1,615 @@
+use super::{
+    primitive::{Surface, tolerance},
+    bvh::{Bounds, Hierarchy},
+};
+use crate::core::{V3, Ray, Side, Intersect, Material, resolve_path};
+
+use std::{
+    cmp::Ordering,
+    collections::BTreeMap,
+    convert::TryFrom,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+use serde::{Serialize, Deserialize};
+use num::Float;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Triangle<M, C>
+where
+    M: Material<C>,
+    C: Float,
+{
+    vertices: [V3<C>; 3],
+    normals: Option<[V3<C>; 3]>,
+    material: M,
+}
+
+impl<M, C> Triangle<M, C>
+where
+    M: Material<C>,
+    C: Float,
+{
+    pub fn new(vertices: [V3<C>; 3], normals: Option<[V3<C>; 3]>, material: M) -> Self {
+        Triangle {
+            vertices: vertices,
+            normals: normals,
+            material: material,
+        }
+    }
+}
+
+pub struct TriangleInfo<C>
+where
+    C: Float,
+{
+    time: C,
+    side: Side,
+    u: C,
+    v: C,
+}
+
+impl<C> PartialEq for TriangleInfo<C>
+where
+    C: Float,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.time.eq(&other.time)
+    }
+}
+
+impl<C> PartialOrd for TriangleInfo<C>
+where
+    C: Float,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.time.partial_cmp(&other.time)
+    }
+}
+
+// Möller–Trumbore, the counterclockwise side is the outer one
+fn intersect_triangle<C>(a: &V3<C>, b: &V3<C>, c: &V3<C>, ray: &Ray<C>) -> Option<TriangleInfo<C>>
+where
+    C: Float,
+{
+    let zero = C::zero();
+    let one = C::one();
+
+    let e1 = b - a;
+    let e2 = c - a;
+    let p = ray.direction().cross(&e2);
+    let det = &e1 * &p;
+    if det.abs() < C::epsilon() {
+        return None;
+    }
+    let inverse = one / det;
+
+    let t = ray.position() - a;
+    let u = (&t * &p) * inverse;
+    if u < zero || u > one {
+        return None;
+    }
+    let q = t.cross(&e1);
+    let v = (ray.direction() * &q) * inverse;
+    if v < zero || u + v > one {
+        return None;
+    }
+
+    let time = (&e2 * &q) * inverse;
+    if time > tolerance() {
+        Some(TriangleInfo {
+            time: time,
+            side: if det > zero { Side::Outer } else { Side::Inner },
+            u: u,
+            v: v,
+        })
+    } else {
+        None
+    }
+}
+
+fn triangle_normal<C>(
+    vertices: [&V3<C>; 3],
+    normals: Option<[&V3<C>; 3]>,
+    info: &TriangleInfo<C>,
+) -> V3<C>
+where
+    C: Float,
+{
+    let normal = match normals {
+        Some([na, nb, nc]) => {
+            let w = C::one() - info.u - info.v;
+            &(&(na * w) + &(nb * info.u)) + &(nc * info.v)
+        },
+        None => (vertices[1] - vertices[0]).cross(&(vertices[2] - vertices[0])),
+    };
+    let normal = normal.normalize();
+    if info.side.outer() {
+        normal
+    } else {
+        -&normal
+    }
+}
+
+impl<M, C> Surface<C> for Triangle<M, C>
+where
+    M: Material<C>,
+    C: Float,
+{
+    type Info = TriangleInfo<C>;
+    type Material = M;
+
+    fn time(info: &Self::Info) -> C {
+        info.time
+    }
+
+    fn bounds(&self) -> Option<Bounds<C>> {
+        Some(Bounds::enclose(self.vertices.iter()))
+    }
+
+    fn intersect(&self, ray: &Ray<C>) -> Option<Self::Info> {
+        let [a, b, c] = &self.vertices;
+        intersect_triangle(a, b, c, ray)
+    }
+
+    fn result<'a>(&'a self, ray: &Ray<C>, info: Self::Info) -> Intersect<'a, Self::Material, C> {
+        let [a, b, c] = &self.vertices;
+        let normals = self.normals.as_ref().map(|[na, nb, nc]| [na, nb, nc]);
+        Intersect {
+            position: ray.position() + &(ray.direction() * info.time),
+            normal: triangle_normal([a, b, c], normals, &info),
+            material: &self.material,
+            side: info.side,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Face {
+    vertices: [usize; 3],
+    normals: Option<[usize; 3]>,
+    material: usize,
+}
+
+/// Triangle mesh sharing vertices and normals between faces, with its own bounding
+/// volume hierarchy over the faces. Deserializes from a description `{ "obj": path, "materials": { group: material } }`
+/// loading the Wavefront OBJ file relative to the scene file, see `Mesh::load_obj`.
+#[derive(Clone, Deserialize)]
+#[serde(
+    try_from = "MeshDescription<M>",
+    bound(deserialize = "M: Deserialize<'de>")
+)]
+pub struct Mesh<M, C>
+where
+    M: Material<C>,
+    C: Float,
+{
+    vertices: Vec<V3<C>>,
+    normals: Vec<V3<C>>,
+    faces: Vec<Face>,
+    materials: Vec<M>,
+    hierarchy: Hierarchy<C>,
+}
+
+#[derive(Deserialize)]
+pub struct MeshDescription<M> {
+    obj: PathBuf,
+    materials: BTreeMap<String, M>,
+    default: Option<M>,
+}
+
+impl<M, C> TryFrom<MeshDescription<M>> for Mesh<M, C>
+where
+    M: Material<C>,
+    C: Float,
+{
+    type Error = ObjError;
+
+    fn try_from(description: MeshDescription<M>) -> Result<Self, Self::Error> {
+        let path = resolve_path(description.obj);
+        Mesh::load_obj(path, description.materials, description.default)
+    }
+}
+
+#[derive(Debug)]
+pub enum ObjError {
+    Io(io::Error),
+    WrongNumber { line: usize },
+    WrongIndex { line: usize },
+    UnknownGroup { line: usize, group: String },
+}
+
+impl fmt::Display for ObjError {
+    #[allow(clippy::needless_borrowed_reference)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            &ObjError::Io(ref e) => write!(f, "cannot read obj file: {}", e),
+            &ObjError::WrongNumber { line: line } => write!(f, "wrong number at line {}", line),
+            &ObjError::WrongIndex { line: line } => write!(f, "wrong index at line {}", line),
+            &ObjError::UnknownGroup {
+                line: line,
+                group: ref group,
+            } => write!(f, "no material for group \'{}\' at line {}", group, line),
+        }
+    }
+}
+
+impl From<io::Error> for ObjError {
+    fn from(e: io::Error) -> Self {
+        ObjError::Io(e)
+    }
+}
+
+impl<M, C> Mesh<M, C>
+where
+    M: Material<C>,
+    C: Float,
+{
+    /// Reads vertices, normals and faces of the Wavefront OBJ file,
+    /// polygons are triangulated as a fan. Each face takes the material of its
+    /// group (`g` or `o` statement), faces outside of any listed group take `default`.
+    pub fn load_obj<P>(
+        path: P,
+        materials: BTreeMap<String, M>,
+        default: Option<M>,
+    ) -> Result<Self, ObjError>
+    where
+        P: AsRef<Path>,
+    {
+        let text = fs::read_to_string(path)?;
+        Self::parse_obj(text.as_str(), materials, default)
+    }
+
+    pub fn parse_obj(
+        text: &str,
+        materials: BTreeMap<String, M>,
+        default: Option<M>,
+    ) -> Result<Self, ObjError> {
+        let mut indices = BTreeMap::new();
+        let mut mesh = Mesh {
+            vertices: Vec::new(),
+            normals: Vec::new(),
+            faces: Vec::new(),
+            materials: Vec::new(),
+            hierarchy: Hierarchy::new(Vec::new()).0,
+        };
+        for (name, material) in materials {
+            indices.insert(name, mesh.materials.len());
+            mesh.materials.push(material);
+        }
+        let default = default.map(|material| {
+            mesh.materials.push(material);
+            mesh.materials.len() - 1
+        });
+
+        let mut group = None;
+        for (number, line) in text.lines().enumerate() {
+            let line_number = number + 1;
+            let line = line.split('#').next().unwrap_or("");
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("v") => mesh.vertices.push(parse_vector(words, line_number)?),
+                Some("vn") => mesh.normals.push(parse_vector(words, line_number)?),
+                Some("g") | Some("o") => group = words.next().map(ToOwned::to_owned),
+                Some("f") => {
+                    let material = match &group {
+                        Some(name) => indices.get(name).cloned().or(default),
+                        None => default,
+                    }
+                    .ok_or_else(|| ObjError::UnknownGroup {
+                        line: line_number,
+                        group: group.clone().unwrap_or_default(),
+                    })?;
+
+                    let corners = words
+                        .map(|word| mesh.parse_corner(word, line_number))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if corners.len() < 3 {
+                        return Err(ObjError::WrongIndex { line: line_number });
+                    }
+                    for i in 1..(corners.len() - 1) {
+                        let (a, b, c) = (corners[0], corners[i], corners[i + 1]);
+                        let normals = match (a.1, b.1, c.1) {
+                            (Some(na), Some(nb), Some(nc)) => Some([na, nb, nc]),
+                            _ => None,
+                        };
+                        mesh.faces.push(Face {
+                            vertices: [a.0, b.0, c.0],
+                            normals: normals,
+                            material: material,
+                        });
+                    }
+                },
+                _ => (),
+            }
+        }
+
+        let bounds = mesh
+            .faces
+            .iter()
+            .map(|face| Bounds::enclose(face.vertices.iter().map(|&i| &mesh.vertices[i])))
+            .collect();
+        let (hierarchy, order) = Hierarchy::new(bounds);
+        mesh.faces = order.into_iter().map(|i| mesh.faces[i].clone()).collect();
+        mesh.hierarchy = hierarchy;
+
+        Ok(mesh)
+    }
+
+    // `v`, `v/vt`, `v//vn` or `v/vt/vn`, indices are one based or negative relative
+    fn parse_corner(&self, word: &str, line: usize) -> Result<(usize, Option<usize>), ObjError> {
+        let resolve = |s: &str, count: usize| -> Result<usize, ObjError> {
+            let index = s
+                .parse::<isize>()
+                .map_err(|_| ObjError::WrongIndex { line: line })?;
+            let index = if index > 0 {
+                index - 1
+            } else {
+                count as isize + index
+            };
+            if index >= 0 && (index as usize) < count {
+                Ok(index as usize)
+            } else {
+                Err(ObjError::WrongIndex { line: line })
+            }
+        };
+
+        let mut parts = word.split('/');
+        let vertex = resolve(parts.next().unwrap_or(""), self.vertices.len())?;
+        let normal = match parts.nth(1) {
+            Some(s) if !s.is_empty() => Some(resolve(s, self.normals.len())?),
+            _ => None,
+        };
+        Ok((vertex, normal))
+    }
+
+    pub fn triangles(&self) -> usize {
+        self.faces.len()
+    }
+}
+
+fn parse_vector<'a, I, C>(words: I, line: usize) -> Result<V3<C>, ObjError>
+where
+    I: Iterator<Item = &'a str>,
+    C: Float,
+{
+    let mut coordinates = words.take(3).map(|word| {
+        word.parse::<f64>()
+            .ok()
+            .and_then(C::from)
+            .ok_or(ObjError::WrongNumber { line: line })
+    });
+    let mut next = || {
+        coordinates
+            .next()
+            .unwrap_or(Err(ObjError::WrongNumber { line: line }))
+    };
+    Ok(V3::new(next()?, next()?, next()?))
+}
+
+pub struct MeshInfo<C>
+where
+    C: Float,
+{
+    face: usize,
+    triangle: TriangleInfo<C>,
+}
+
+impl<C> PartialEq for MeshInfo<C>
+where
+    C: Float,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.triangle.eq(&other.triangle)
+    }
+}
+
+impl<C> PartialOrd for MeshInfo<C>
+where
+    C: Float,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.triangle.partial_cmp(&other.triangle)
+    }
+}
+
+impl<M, C> Surface<C> for Mesh<M, C>
+where
+    M: Material<C>,
+    C: Float,
+{
+    type Info = MeshInfo<C>;
+    type Material = M;
+
+    fn time(info: &Self::Info) -> C {
+        info.triangle.time
+    }
+
+    fn bounds(&self) -> Option<Bounds<C>> {
+        Some(Bounds::enclose(self.vertices.iter()))
+    }
+
+    fn intersect(&self, ray: &Ray<C>) -> Option<Self::Info> {
+        self.hierarchy.nearest(ray, C::infinity(), |index| {
+            let [a, b, c] = self.faces[index].vertices;
+            let (a, b, c) = (&self.vertices[a], &self.vertices[b], &self.vertices[c]);
+            intersect_triangle(a, b, c, ray).map(|triangle| {
+                (
+                    triangle.time,
+                    MeshInfo {
+                        face: index,
+                        triangle: triangle,
+                    },
+                )
+            })
+        })
+    }
+
+    fn result<'a>(&'a self, ray: &Ray<C>, info: Self::Info) -> Intersect<'a, Self::Material, C> {
+        let face = &self.faces[info.face];
+        let [a, b, c] = face.vertices;
+        let vertices = [&self.vertices[a], &self.vertices[b], &self.vertices[c]];
+        let normals = face
+            .normals
+            .map(|[a, b, c]| [&self.normals[a], &self.normals[b], &self.normals[c]]);
+        let info = info.triangle;
+        Intersect {
+            position: ray.position() + &(ray.direction() * info.time),
+            normal: triangle_normal(vertices, normals, &info),
+            material: &self.materials[face.material],
+            side: info.side,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Surface, Triangle, Mesh, ObjError};
+    use crate::{
+        core::{V3, Ray, Scene, WaveLength},
+        light::CustomMaterial,
+    };
+    use std::collections::BTreeMap;
+    use rand::Rng;
+
+    const QUAD: &str = "
+        # unit square in the xy plane
+        v -1 -1 0
+        v 1 -1 0
+        v 1 1 0
+        v -1 1 0
+        vn 0 0 1
+        g floor
+        f 1//1 2//1 3//1 4//1
+    ";
+
+    #[test]
+    fn quad() {
+        let mut materials = BTreeMap::new();
+        materials.insert("floor".to_owned(), CustomMaterial::DiffuseWhite);
+        let mesh: Mesh<_, f64> = Mesh::parse_obj(QUAD, materials, None).unwrap();
+        assert_eq!(mesh.triangles(), 2);
+
+        let ray = Ray::new(
+            V3::new(0.5, 0.5, 2.0),
+            V3::new(0.0, 0.0, -1.0),
+            WaveLength(550.0),
+        );
+        let info = mesh.intersect(&ray).unwrap();
+        let result = mesh.result(&ray, info);
+        assert!(result.side.outer());
+        assert!(result.position.z().abs() < 1e-12);
+        assert!((result.normal.z() - 1.0).abs() < 1e-12);
+
+        let ray = Ray::new(
+            V3::new(0.5, 0.5, -2.0),
+            V3::new(0.0, 0.0, 1.0),
+            WaveLength(550.0),
+        );
+        let info = mesh.intersect(&ray).unwrap();
+        let result = mesh.result(&ray, info);
+        assert!(!result.side.outer());
+        assert!((result.normal.z() + 1.0).abs() < 1e-12);
+
+        let ray = Ray::new(
+            V3::new(1.5, 0.5, 2.0),
+            V3::new(0.0, 0.0, -1.0),
+            WaveLength(550.0),
+        );
+        assert!(mesh.intersect(&ray).is_none());
+    }
+
+    #[test]
+    fn unknown_group() {
+        let result = Mesh::<CustomMaterial, f64>::parse_obj(QUAD, BTreeMap::new(), None);
+        match result {
+            Err(ObjError::UnknownGroup { line: 9, .. }) => (),
+            _ => panic!("expected unknown group error"),
+        }
+    }
+
+    #[test]
+    fn same_as_triangles() {
+        use std::f64::consts::PI;
+
+        // the latitude longitude sphere, quads between the rings
+        let (rings, segments) = (24, 48);
+        let mut obj = String::new();
+        let mut vertices = Vec::new();
+        for i in 0..=rings {
+            let theta = PI * (i as f64) / (rings as f64);
+            for j in 0..segments {
+                let phi = 2.0 * PI * (j as f64) / (segments as f64);
+                let v = V3::new(
+                    theta.sin() * phi.cos(),
+                    theta.sin() * phi.sin(),
+                    theta.cos(),
+                );
+                obj += format!("v {} {} {}\n", v.x(), v.y(), v.z()).as_str();
+                vertices.push(v);
+            }
+        }
+        let mut triangles = Vec::new();
+        for i in 0..rings {
+            for j in 0..segments {
+                let index = |i: usize, j: usize| i * segments + j % segments;
+                let quad = [
+                    index(i, j),
+                    index(i + 1, j),
+                    index(i + 1, j + 1),
+                    index(i, j + 1),
+                ];
+                obj += format!(
+                    "f {} {} {} {}\n",
+                    quad[0] + 1,
+                    quad[1] + 1,
+                    quad[2] + 1,
+                    quad[3] + 1
+                )
+                .as_str();
+                for &(a, b, c) in [(0, 1, 2), (0, 2, 3)].iter() {
+                    let corners = [
+                        vertices[quad[a]].clone(),
+                        vertices[quad[b]].clone(),
+                        vertices[quad[c]].clone(),
+                    ];
+                    triangles.push(Triangle::new(corners, None, CustomMaterial::DiffuseWhite));
+                }
+            }
+        }
+        let mesh: Mesh<_, f64> = Mesh::parse_obj(
+            obj.as_str(),
+            BTreeMap::new(),
+            Some(CustomMaterial::DiffuseWhite),
+        )
+        .unwrap();
+        assert_eq!(mesh.triangles(), triangles.len());
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let position = V3::new(
+                rng.gen_range(-2.0..2.0),
+                rng.gen_range(-2.0..2.0),
+                rng.gen_range(-2.0..2.0),
+            );
+            let direction = V3::new(
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+            )
+            .normalize();
+            let ray = Ray::new(position, direction, WaveLength(550.0));
+            let expected = triangles.find_intersect(&ray);
+            let actual = mesh.intersect(&ray).map(|info| mesh.result(&ray, info));
+            match (expected, actual) {
+                (Some(expected), Some(actual)) => {
+                    assert_eq!(expected.side.outer(), actual.side.outer());
+                    let delta = &expected.position - &actual.position;
+                    assert!((&delta * &delta) < 1e-12);
+                },
+                (None, None) => (),
+                _ => panic!("the mesh and the triangles disagree"),
+            }
+        }
+    }
+}
@@ -1,3 +1,9 @@
 mod primitive;
+mod mesh;
+mod bvh;
+mod compose;
 
-pub use self::primitive::{Surface, Sphere};
+pub use self::primitive::{Surface, Sphere, Plane, AxisAlignedBox};
+pub use self::mesh::{Triangle, Mesh, MeshDescription, ObjError};
+pub use self::bvh::{Bounds, Bvh};
+pub use self::compose::{Primitive, Union};
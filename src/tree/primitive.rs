@@ -1,4 +1,5 @@
-use crate::core::{V3, Ray, Scene, Side, Intersect, Material};
+use super::bvh::Bounds;
+use crate::core::{V3, Ray, Scene, Side, Intersect, Material, Light};
 
 use std::cmp::Ordering;
 use serde::{Serialize, Deserialize};
@@ -16,6 +17,19 @@ where
 
     fn result<'a>(&'a self, ray: &Ray<C>, info: Self::Info) -> Intersect<'a, Self::Material, C>;
 
+    /// Distance along the ray, the order of `Self::Info` agrees with it.
+    fn time(info: &Self::Info) -> C;
+
+    /// Axis aligned box containing the surface, `None` if the surface is unbounded.
+    fn bounds(&self) -> Option<Bounds<C>> {
+        None
+    }
+
+    /// The surface as the light sampled directly, only emissive spheres are.
+    fn light(&self) -> Option<Light<C>> {
+        None
+    }
+
     fn find_intersect<'a>(v: &'a [Self], ray: &Ray<C>) -> Option<(&'a Self, Self::Info)> {
         v.iter()
             .flat_map(|this| this.intersect(ray).map(|info| (this, info)))
@@ -23,6 +37,15 @@ where
     }
 }
 
+/// Intersections closer than this are treated as the ray hitting the surface
+/// it has just left.
+pub(super) fn tolerance<C>() -> C
+where
+    C: Float,
+{
+    C::epsilon().sqrt()
+}
+
 impl<S, C> Scene<C> for Vec<S>
 where
     S: Surface<C>,
@@ -33,6 +56,10 @@ where
     fn find_intersect<'a>(&'a self, ray: &Ray<C>) -> Option<Intersect<'a, Self::Material, C>> {
         Surface::find_intersect(self.as_ref(), ray).map(|(this, info)| this.result(ray, info))
     }
+
+    fn lights(&self) -> Vec<Light<C>> {
+        self.iter().flat_map(Surface::light).collect()
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -94,6 +121,23 @@ where
     type Info = SphereInfo<C>;
     type Material = M;
 
+    fn time(info: &Self::Info) -> C {
+        info.time
+    }
+
+    fn bounds(&self) -> Option<Bounds<C>> {
+        let r = V3::new(self.radius, self.radius, self.radius).abs();
+        Some(Bounds::new(&self.center - &r, &self.center + &r))
+    }
+
+    fn light(&self) -> Option<Light<C>> {
+        if self.material.emissive() {
+            Some(Light::new(self.center.clone(), self.radius))
+        } else {
+            None
+        }
+    }
+
     fn intersect(&self, ray: &Ray<C>) -> Option<Self::Info> {
         use num::Zero;
 
@@ -104,29 +148,27 @@ where
         let r = self.radius;
 
         let b = p * &q;
-        let (outer, d) = {
-            let s = &q * &q - r * r;
-            (s >= zero, b * b - s)
-        };
+        let d = b * b - (&q * &q - r * r);
+        if d < zero {
+            return None;
+        }
 
-        let time = if d < zero {
-            None
+        // the side follows the root, the origin lying on the sphere is ambiguous
+        let t0 = b - d.sqrt();
+        let t1 = b + d.sqrt();
+        if t0 > tolerance() {
+            Some(SphereInfo {
+                time: t0,
+                side: Side::Outer,
+            })
+        } else if t1 > tolerance() {
+            Some(SphereInfo {
+                time: t1,
+                side: Side::Inner,
+            })
         } else {
-            let t0 = b - d.sqrt();
-            let t1 = b + d.sqrt();
-            if t0 >= zero {
-                Some(t0)
-            } else if t1 >= zero {
-                Some(t1)
-            } else {
-                None
-            }
-        };
-
-        time.map(|time| SphereInfo {
-            time: time,
-            side: if outer { Side::Outer } else { Side::Inner },
-        })
+            None
+        }
     }
 
     fn result<'a>(&'a self, ray: &Ray<C>, info: Self::Info) -> Intersect<'a, Self::Material, C> {
@@ -145,3 +187,377 @@ where
         }
     }
 }
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Plane<M, C>
+where
+    M: Material<C>,
+    C: Float,
+{
+    point: V3<C>,
+    normal: V3<C>,
+    material: M,
+}
+
+impl<M, C> Plane<M, C>
+where
+    M: Material<C>,
+    C: Float,
+{
+    pub fn new(point: V3<C>, normal: V3<C>, material: M) -> Self {
+        Plane {
+            point: point,
+            normal: normal.normalize(),
+            material: material,
+        }
+    }
+}
+
+pub struct PlaneInfo<C>
+where
+    C: Float,
+{
+    time: C,
+    side: Side,
+}
+
+impl<C> PartialEq for PlaneInfo<C>
+where
+    C: Float,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.time.eq(&other.time)
+    }
+}
+
+impl<C> PartialOrd for PlaneInfo<C>
+where
+    C: Float,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.time.partial_cmp(&other.time)
+    }
+}
+
+impl<M, C> Surface<C> for Plane<M, C>
+where
+    M: Material<C>,
+    C: Float,
+{
+    type Info = PlaneInfo<C>;
+    type Material = M;
+
+    fn time(info: &Self::Info) -> C {
+        info.time
+    }
+
+    fn intersect(&self, ray: &Ray<C>) -> Option<Self::Info> {
+        let zero = C::zero();
+
+        let q = &self.point - ray.position();
+        let distance = &q * &self.normal;
+        let speed = ray.direction() * &self.normal;
+        if speed == zero {
+            return None;
+        }
+
+        // the normal points to the outer half-space
+        let time = distance / speed;
+        if time > tolerance() {
+            Some(PlaneInfo {
+                time: time,
+                side: if distance <= zero {
+                    Side::Outer
+                } else {
+                    Side::Inner
+                },
+            })
+        } else {
+            None
+        }
+    }
+
+    fn result<'a>(&'a self, ray: &Ray<C>, info: Self::Info) -> Intersect<'a, Self::Material, C> {
+        let position = ray.position() + &(ray.direction() * info.time);
+        let normal = self.normal.normalize();
+        let normal = if info.side.outer() { normal } else { -&normal };
+        Intersect {
+            position: position,
+            normal: normal,
+            material: &self.material,
+            side: info.side,
+        }
+    }
+}
+
+/// In the scene file the corners may come in any order, they are sorted as by `new`.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(from = "AxisAlignedBoxDescription<M, C>")]
+pub struct AxisAlignedBox<M, C>
+where
+    M: Material<C>,
+    C: Float,
+{
+    min: V3<C>,
+    max: V3<C>,
+    material: M,
+}
+
+#[derive(Deserialize)]
+struct AxisAlignedBoxDescription<M, C>
+where
+    C: Float,
+{
+    min: V3<C>,
+    max: V3<C>,
+    material: M,
+}
+
+impl<M, C> From<AxisAlignedBoxDescription<M, C>> for AxisAlignedBox<M, C>
+where
+    M: Material<C>,
+    C: Float,
+{
+    fn from(description: AxisAlignedBoxDescription<M, C>) -> Self {
+        AxisAlignedBox::new(description.min, description.max, description.material)
+    }
+}
+
+impl<M, C> AxisAlignedBox<M, C>
+where
+    M: Material<C>,
+    C: Float,
+{
+    pub fn new(min: V3<C>, max: V3<C>, material: M) -> Self {
+        AxisAlignedBox {
+            min: min.min(&max),
+            max: min.max(&max),
+            material: material,
+        }
+    }
+}
+
+pub struct AxisAlignedBoxInfo<C>
+where
+    C: Float,
+{
+    time: C,
+    side: Side,
+    normal: V3<C>,
+}
+
+impl<C> PartialEq for AxisAlignedBoxInfo<C>
+where
+    C: Float,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.time.eq(&other.time)
+    }
+}
+
+impl<C> PartialOrd for AxisAlignedBoxInfo<C>
+where
+    C: Float,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.time.partial_cmp(&other.time)
+    }
+}
+
+impl<M, C> Surface<C> for AxisAlignedBox<M, C>
+where
+    M: Material<C>,
+    C: Float,
+{
+    type Info = AxisAlignedBoxInfo<C>;
+    type Material = M;
+
+    fn time(info: &Self::Info) -> C {
+        info.time
+    }
+
+    fn bounds(&self) -> Option<Bounds<C>> {
+        Some(Bounds::new(self.min.clone(), self.max.clone()))
+    }
+
+    fn intersect(&self, ray: &Ray<C>) -> Option<Self::Info> {
+        let zero = C::zero();
+        let one = C::one();
+
+        let p = ray.position();
+        let d = ray.direction();
+        let slabs = [
+            (p.x(), d.x(), self.min.x(), self.max.x()),
+            (p.y(), d.y(), self.min.y(), self.max.y()),
+            (p.z(), d.z(), self.min.z(), self.max.z()),
+        ];
+
+        // slab method, remember the axis where the near and the far time are reached
+        let mut near = (C::neg_infinity(), 0);
+        let mut far = (C::infinity(), 0);
+        for (axis, &(p, d, min, max)) in slabs.iter().enumerate() {
+            if d == zero {
+                if p < min || p > max {
+                    return None;
+                }
+            } else {
+                let t0 = (min - p) / d;
+                let t1 = (max - p) / d;
+                let (t0, t1) = if t0 < t1 { (t0, t1) } else { (t1, t0) };
+                if t0 > near.0 {
+                    near = (t0, axis);
+                }
+                if t1 < far.0 {
+                    far = (t1, axis);
+                }
+            }
+        }
+
+        if near.0 > far.0 || far.0 <= tolerance() {
+            return None;
+        }
+
+        let (time, axis, side) = if near.0 > tolerance() {
+            (near.0, near.1, Side::Outer)
+        } else {
+            (far.0, far.1, Side::Inner)
+        };
+
+        // both for the entry and for the exit face the normal looks against the ray
+        let sign = if slabs[axis].1 > zero { -one } else { one };
+        let normal = match axis {
+            0 => V3::new(sign, zero, zero),
+            1 => V3::new(zero, sign, zero),
+            _ => V3::new(zero, zero, sign),
+        };
+
+        Some(AxisAlignedBoxInfo {
+            time: time,
+            side: side,
+            normal: normal,
+        })
+    }
+
+    fn result<'a>(&'a self, ray: &Ray<C>, info: Self::Info) -> Intersect<'a, Self::Material, C> {
+        let position = ray.position() + &(ray.direction() * info.time);
+        Intersect {
+            position: position,
+            normal: info.normal,
+            material: &self.material,
+            side: info.side,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Surface, Sphere, Plane, AxisAlignedBox};
+    use crate::{
+        core::{V3, Ray, WaveLength},
+        light::CustomMaterial,
+    };
+
+    fn ray(position: V3<f64>, direction: V3<f64>) -> Ray<f64> {
+        Ray::new(position, direction.normalize(), WaveLength(550.0))
+    }
+
+    #[test]
+    fn sphere() {
+        let sphere = Sphere::new(V3::new(0.0, 0.0, 0.0), 1.0, CustomMaterial::DiffuseWhite);
+
+        let r = ray(V3::new(0.0, 0.0, -5.0), V3::new(0.0, 0.0, 1.0));
+        let info = sphere.intersect(&r).unwrap();
+        let result = sphere.result(&r, info);
+        assert!(result.side.outer());
+        assert!((result.position.z() + 1.0).abs() < 1e-12);
+        assert!((result.normal.z() + 1.0).abs() < 1e-12);
+
+        // the ray leaving the front of the sphere after the refraction hits its back
+        // from the inside, wherever the rounding puts its origin
+        for &z in [-1.0 - 1e-12, -1.0, -1.0 + 1e-12].iter() {
+            let r = ray(V3::new(0.0, 0.0, z), V3::new(0.0, 0.0, 1.0));
+            let info = sphere.intersect(&r).unwrap();
+            let result = sphere.result(&r, info);
+            assert!(!result.side.outer());
+            assert!((result.position.z() - 1.0).abs() < 1e-9);
+            assert!((result.normal.z() + 1.0).abs() < 1e-9);
+        }
+
+        // the ray reflected off the sphere does not hit it again
+        for &z in [-1.0 - 1e-12, -1.0, -1.0 + 1e-12].iter() {
+            let r = ray(V3::new(0.0, 0.0, z), V3::new(0.0, 0.0, -1.0));
+            assert!(sphere.intersect(&r).is_none());
+        }
+    }
+
+    #[test]
+    fn plane() {
+        let plane = Plane::new(
+            V3::new(0.0, -1.0, 0.0),
+            V3::new(0.0, 1.0, 0.0),
+            CustomMaterial::DiffuseWhite,
+        );
+
+        let r = ray(V3::new(0.0, 1.0, 0.0), V3::new(0.0, -1.0, 0.0));
+        let info = plane.intersect(&r).unwrap();
+        let result = plane.result(&r, info);
+        assert!(result.side.outer());
+        assert!((result.position.y() + 1.0).abs() < 1e-12);
+        assert!((result.normal.y() - 1.0).abs() < 1e-12);
+
+        let r = ray(V3::new(0.0, -3.0, 0.0), V3::new(0.0, 1.0, 0.0));
+        let info = plane.intersect(&r).unwrap();
+        let result = plane.result(&r, info);
+        assert!(!result.side.outer());
+        assert!((result.normal.y() + 1.0).abs() < 1e-12);
+
+        let r = ray(V3::new(0.0, 1.0, 0.0), V3::new(0.0, 1.0, 0.0));
+        assert!(plane.intersect(&r).is_none());
+        let r = ray(V3::new(0.0, 1.0, 0.0), V3::new(1.0, 0.0, 0.0));
+        assert!(plane.intersect(&r).is_none());
+    }
+
+    #[test]
+    fn axis_aligned_box() {
+        let b = AxisAlignedBox::new(
+            V3::new(1.0, 1.0, 1.0),
+            V3::new(-1.0, -1.0, -1.0),
+            CustomMaterial::DiffuseWhite,
+        );
+
+        let r = ray(V3::new(-5.0, 0.5, 0.0), V3::new(1.0, 0.0, 0.0));
+        let info = b.intersect(&r).unwrap();
+        let result = b.result(&r, info);
+        assert!(result.side.outer());
+        assert!((result.position.x() + 1.0).abs() < 1e-12);
+        assert!((result.normal.x() + 1.0).abs() < 1e-12);
+
+        let r = ray(V3::new(0.0, 0.0, 0.0), V3::new(0.0, 0.0, 1.0));
+        let info = b.intersect(&r).unwrap();
+        let result = b.result(&r, info);
+        assert!(!result.side.outer());
+        assert!((result.position.z() - 1.0).abs() < 1e-12);
+        assert!((result.normal.z() + 1.0).abs() < 1e-12);
+
+        let r = ray(V3::new(-5.0, 2.0, 0.0), V3::new(1.0, 0.0, 0.0));
+        assert!(b.intersect(&r).is_none());
+        let r = ray(V3::new(5.0, 0.0, 0.0), V3::new(1.0, 0.0, 0.0));
+        assert!(b.intersect(&r).is_none());
+    }
+
+    #[test]
+    fn axis_aligned_box_swapped() {
+        let json = r#"{
+            "min": { "x": 1.0, "y": 1.0, "z": 1.0 },
+            "max": { "x": -1.0, "y": -1.0, "z": -1.0 },
+            "material": "DiffuseWhite"
+        }"#;
+        let b: AxisAlignedBox<CustomMaterial, f64> = serde_json::from_str(json).unwrap();
+
+        let r = ray(V3::new(-5.0, 0.5, 0.0), V3::new(1.0, 0.0, 0.0));
+        let info = b.intersect(&r).unwrap();
+        let result = b.result(&r, info);
+        assert!(result.side.outer());
+        assert!((result.position.x() + 1.0).abs() < 1e-12);
+    }
+}